@@ -0,0 +1,207 @@
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+use glob::glob;
+use ignore::WalkBuilder;
+use super::confirm::Confirmer;
+use super::filesystem::{ActionType, FileSystemAction};
+use super::fs_backend::FileSystem;
+use super::safety::{expand_path, SafetyLevel, SafetyValidator};
+
+/// Outcome of a single file within a `BatchAction`.
+#[derive(Debug, Clone)]
+pub enum BatchOutcome {
+    Succeeded(String),
+    SkippedIgnored,
+    SkippedBlocked,
+    FailedWithError(String),
+}
+
+#[derive(Debug, Clone)]
+pub struct BatchResult {
+    pub path: PathBuf,
+    pub outcome: BatchOutcome,
+}
+
+/// Expands a glob pattern (e.g. `~/Downloads/*.tmp`) into individually-validated
+/// `FileSystemAction`s, skipping anything a `.gitignore` encountered along the walk
+/// excludes and anything `SafetyValidator` classifies as `Blocked`.
+pub struct BatchAction {
+    pub pattern: String,
+    pub action_type: ActionType,
+    pub destination_dir: Option<PathBuf>,
+}
+
+impl BatchAction {
+    pub fn new(pattern: impl Into<String>, action_type: ActionType) -> Self {
+        Self {
+            pattern: pattern.into(),
+            action_type,
+            destination_dir: None,
+        }
+    }
+
+    pub fn with_destination_dir(mut self, dir: PathBuf) -> Self {
+        self.destination_dir = Some(dir);
+        self
+    }
+
+    /// Expand `pattern` into matching paths without applying any filtering. `~` and
+    /// `$VAR`/`${VAR}` are expanded first (via `safety::expand_path`), since the `glob`
+    /// crate has no notion of either and would otherwise match nothing against a
+    /// pattern like `~/Downloads/*.tmp`.
+    pub fn expand(&self) -> Result<Vec<PathBuf>, String> {
+        let pattern = self.expanded_pattern();
+        glob(&pattern)
+            .map_err(|e| format!("Invalid glob pattern '{}': {}", pattern, e))?
+            .filter_map(|entry| entry.ok())
+            .map(Ok)
+            .collect()
+    }
+
+    /// `pattern` with `~`/env-var expansion applied, as a `glob`-ready string.
+    fn expanded_pattern(&self) -> String {
+        expand_path(&self.pattern).to_string_lossy().into_owned()
+    }
+
+    fn build_action(&self, path: &Path) -> FileSystemAction {
+        let mut action = FileSystemAction::new(self.action_type.clone(), path.to_path_buf());
+        if let Some(dest_dir) = &self.destination_dir {
+            if let Some(file_name) = path.file_name() {
+                action = action.with_destination(dest_dir.join(file_name));
+            }
+        }
+        action
+    }
+
+    /// Validate and execute every file matched by `pattern` against `fs`, prompting
+    /// `confirmer` once (via `multi_select`) for everything that needs approval.
+    pub fn execute<F: FileSystem, C: Confirmer>(
+        &self,
+        validator: &SafetyValidator,
+        confirmer: &C,
+        fs: &F,
+    ) -> Vec<BatchResult> {
+        let candidates = match self.expand() {
+            Ok(paths) => paths,
+            Err(e) => {
+                return vec![BatchResult {
+                    path: PathBuf::from(&self.pattern),
+                    outcome: BatchOutcome::FailedWithError(e),
+                }]
+            }
+        };
+
+        let not_ignored = self.not_ignored_set();
+        let mut results = Vec::new();
+        let mut pending: Vec<(PathBuf, FileSystemAction)> = Vec::new();
+
+        for path in candidates {
+            if !not_ignored.contains(&path) {
+                results.push(BatchResult { path, outcome: BatchOutcome::SkippedIgnored });
+                continue;
+            }
+
+            match validator.check_path_in(&path, fs) {
+                SafetyLevel::Blocked => {
+                    results.push(BatchResult { path, outcome: BatchOutcome::SkippedBlocked });
+                }
+                SafetyLevel::Safe => {
+                    let action = self.build_action(&path);
+                    results.push(run_action(path, &action, fs));
+                }
+                SafetyLevel::NeedsConfirmation => {
+                    let action = self.build_action(&path);
+                    pending.push((path, action));
+                }
+            }
+        }
+
+        if !pending.is_empty() {
+            let labels: Vec<String> = pending.iter().map(|(p, _)| p.display().to_string()).collect();
+            let approved = confirmer.multi_select("Approve these operations?", &labels);
+            for (i, (path, action)) in pending.into_iter().enumerate() {
+                if approved.contains(&i) {
+                    results.push(run_action(path, &action, fs));
+                } else {
+                    results.push(BatchResult {
+                        path,
+                        outcome: BatchOutcome::FailedWithError("not approved".to_string()),
+                    });
+                }
+            }
+        }
+
+        results
+    }
+
+    /// Paths under the pattern's base directory that survive `.gitignore` filtering.
+    /// Delegates the hierarchical per-directory ignore-rule parsing to
+    /// `ignore::WalkBuilder`, which walks that base directory honoring every
+    /// `.gitignore` (and negation) found along the way, the same way `git status` would.
+    fn not_ignored_set(&self) -> HashSet<PathBuf> {
+        let root = glob_base_dir(&self.expanded_pattern());
+        WalkBuilder::new(&root)
+            .hidden(false)
+            .build()
+            .filter_map(|entry| entry.ok())
+            // `glob` yields `a.tmp` for a relative pattern where a walk from `.` yields `./a.tmp`
+            .map(|entry| {
+                let path = entry.into_path();
+                path.strip_prefix(".").map(Path::to_path_buf).unwrap_or(path)
+            })
+            .collect()
+    }
+}
+
+fn run_action<F: FileSystem>(path: PathBuf, action: &FileSystemAction, fs: &F) -> BatchResult {
+    match action.execute_on(fs) {
+        Ok(msg) => BatchResult { path, outcome: BatchOutcome::Succeeded(msg) },
+        Err(e) => BatchResult { path, outcome: BatchOutcome::FailedWithError(e) },
+    }
+}
+
+/// The longest path prefix of `pattern` that contains no glob metacharacters, used as
+/// the root to walk when applying `.gitignore` rules.
+fn glob_base_dir(pattern: &str) -> PathBuf {
+    let mut base = PathBuf::new();
+    for component in Path::new(pattern).components() {
+        let part = component.as_os_str().to_string_lossy();
+        if part.contains(['*', '?', '[', ']']) {
+            break;
+        }
+        base.push(component);
+    }
+    if base.as_os_str().is_empty() {
+        PathBuf::from(".")
+    } else {
+        base
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_glob_base_dir_stops_before_wildcard() {
+        assert_eq!(glob_base_dir("~/Downloads/*.tmp"), PathBuf::from("~/Downloads"));
+        assert_eq!(glob_base_dir("/var/log/app/**/*.log"), PathBuf::from("/var/log/app"));
+        assert_eq!(glob_base_dir("*.tmp"), PathBuf::from("."));
+    }
+
+    #[test]
+    fn test_expand_tilde_pattern_matches_real_home_file() {
+        let home = dirs::home_dir().unwrap();
+        let marker_dir = home.join("fluffy_batch_tilde_test");
+        std::fs::create_dir_all(&marker_dir).unwrap();
+        let marker_file = marker_dir.join("marker.tmp");
+        std::fs::write(&marker_file, b"x").unwrap();
+
+        let action = BatchAction::new("~/fluffy_batch_tilde_test/*.tmp", ActionType::DeleteFile);
+        let matches = action.expand().unwrap();
+
+        let _ = std::fs::remove_dir_all(&marker_dir);
+
+        assert_eq!(matches, vec![marker_file]);
+    }
+}
@@ -0,0 +1,114 @@
+use std::io::{self, Write};
+use super::filesystem::FileSystemAction;
+
+/// Prompts the user before a `NeedsConfirmation` action proceeds. Implementations
+/// range from an interactive stdin/stdout prompt to fixed yes/no answers for
+/// non-interactive runs (scripts, tests, background services).
+pub trait Confirmer {
+    /// Ask whether `action` should proceed, given `reason` (e.g. why it isn't `Safe`).
+    fn confirm(&self, action: &FileSystemAction, reason: &str) -> bool;
+
+    /// Ask the user to pick one option from `options`, returning its index.
+    fn select(&self, prompt: &str, options: &[String]) -> Option<usize> {
+        let _ = (prompt, options);
+        None
+    }
+
+    /// Ask the user to pick any number of options from `options`, returning their indices.
+    fn multi_select(&self, prompt: &str, options: &[String]) -> Vec<usize> {
+        let _ = (prompt, options);
+        Vec::new()
+    }
+}
+
+/// Interactive yes/no prompt on stdin/stdout.
+pub struct CliConfirmer;
+
+impl Confirmer for CliConfirmer {
+    fn confirm(&self, action: &FileSystemAction, reason: &str) -> bool {
+        print!(
+            "{} ({}) [y/N]: ",
+            reason,
+            action.target_path.display()
+        );
+        let _ = io::stdout().flush();
+
+        let mut input = String::new();
+        if io::stdin().read_line(&mut input).is_err() {
+            return false;
+        }
+        matches!(input.trim().to_lowercase().as_str(), "y" | "yes")
+    }
+
+    fn select(&self, prompt: &str, options: &[String]) -> Option<usize> {
+        println!("{}", prompt);
+        for (i, opt) in options.iter().enumerate() {
+            println!("  {}) {}", i + 1, opt);
+        }
+        print!("Choice: ");
+        let _ = io::stdout().flush();
+
+        let mut input = String::new();
+        if io::stdin().read_line(&mut input).is_err() {
+            return None;
+        }
+        input.trim().parse::<usize>().ok().and_then(|n| n.checked_sub(1)).filter(|&i| i < options.len())
+    }
+
+    fn multi_select(&self, prompt: &str, options: &[String]) -> Vec<usize> {
+        println!("{}", prompt);
+        for (i, opt) in options.iter().enumerate() {
+            println!("  {}) {}", i + 1, opt);
+        }
+        print!("Choices (comma-separated, blank for none): ");
+        let _ = io::stdout().flush();
+
+        let mut input = String::new();
+        if io::stdin().read_line(&mut input).is_err() {
+            return Vec::new();
+        }
+        input
+            .trim()
+            .split(',')
+            .filter_map(|s| s.trim().parse::<usize>().ok())
+            .filter_map(|n| n.checked_sub(1))
+            .filter(|&i| i < options.len())
+            .collect()
+    }
+}
+
+/// Approves every confirmation without prompting. For non-interactive runs that have
+/// already decided to trust `NeedsConfirmation` actions.
+pub struct AutoApprove;
+
+impl Confirmer for AutoApprove {
+    fn confirm(&self, _action: &FileSystemAction, _reason: &str) -> bool {
+        true
+    }
+
+    fn select(&self, _prompt: &str, options: &[String]) -> Option<usize> {
+        if options.is_empty() { None } else { Some(0) }
+    }
+
+    fn multi_select(&self, _prompt: &str, options: &[String]) -> Vec<usize> {
+        (0..options.len()).collect()
+    }
+}
+
+/// Denies every confirmation without prompting. For non-interactive runs that should
+/// never perform anything short of `Safe`.
+pub struct AutoDeny;
+
+impl Confirmer for AutoDeny {
+    fn confirm(&self, _action: &FileSystemAction, _reason: &str) -> bool {
+        false
+    }
+
+    fn select(&self, _prompt: &str, _options: &[String]) -> Option<usize> {
+        None
+    }
+
+    fn multi_select(&self, _prompt: &str, _options: &[String]) -> Vec<usize> {
+        Vec::new()
+    }
+}
@@ -1,6 +1,7 @@
-use std::path::PathBuf;
-use std::fs;
+use std::path::{Path, PathBuf};
 use serde::{Deserialize, Serialize};
+use super::confirm::Confirmer;
+use super::fs_backend::{FileSystem, RealFs};
 use super::safety::{SafetyValidator, SafetyLevel};
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -11,14 +12,21 @@ pub enum ActionType {
     DeleteFolder,
     MoveFile,
     CopyFile,
+    MoveFolder,
+    CopyFolder,
 }
 
+/// Called as a recursive folder copy/move progresses, with bytes completed so far,
+/// the total bytes being transferred, and the file currently being transferred.
+pub type ProgressCallback<'a> = dyn FnMut(u64, u64, &Path) + 'a;
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct FileSystemAction {
     pub action_type: ActionType,
     pub target_path: PathBuf,
     pub destination_path: Option<PathBuf>, // For move/copy operations
     pub content: Option<String>,           // For file creation
+    pub mode: Option<u32>,                 // Unix permission bits, applied after writing
 }
 
 impl FileSystemAction {
@@ -28,6 +36,7 @@ impl FileSystemAction {
             target_path,
             destination_path: None,
             content: None,
+            mode: None,
         }
     }
 
@@ -41,11 +50,22 @@ impl FileSystemAction {
         self
     }
 
-    /// Validate action against safety rules
+    pub fn with_mode(mut self, mode: u32) -> Self {
+        self.mode = Some(mode);
+        self
+    }
+
+    /// Validate action against safety rules, checking path existence on the real disk.
     pub fn validate(&self, validator: &SafetyValidator) -> Result<SafetyLevel, String> {
+        self.validate_on(validator, &RealFs)
+    }
+
+    /// Validate action against safety rules, checking path existence against `fs`
+    /// instead of the real disk.
+    pub fn validate_on<F: FileSystem>(&self, validator: &SafetyValidator, fs: &F) -> Result<SafetyLevel, String> {
         // Check target path
-        let target_safety = validator.check_path(&self.target_path);
-        
+        let target_safety = validator.check_path_in(&self.target_path, fs);
+
         if matches!(target_safety, SafetyLevel::Blocked) {
             return Err(format!(
                 "Operation blocked: {} is a protected system path",
@@ -55,7 +75,7 @@ impl FileSystemAction {
 
         // Check destination path if applicable
         if let Some(dest) = &self.destination_path {
-            let dest_safety = validator.check_path(dest);
+            let dest_safety = validator.check_path_in(dest, fs);
             if matches!(dest_safety, SafetyLevel::Blocked) {
                 return Err(format!(
                     "Operation blocked: {} is a protected system path",
@@ -67,79 +87,144 @@ impl FileSystemAction {
         Ok(target_safety)
     }
 
-    /// Execute the filesystem action
+    /// Execute the filesystem action against the real disk.
     pub fn execute(&self) -> Result<String, String> {
+        self.execute_on(&RealFs)
+    }
+
+    /// Validate, consult `confirmer` on `NeedsConfirmation`, and only then execute
+    /// against the real disk. Enforces the safety classification at execution time
+    /// instead of leaving it to each caller to check `validate`'s result.
+    pub fn execute_checked<C: Confirmer>(
+        &self,
+        validator: &SafetyValidator,
+        confirmer: &C,
+    ) -> Result<String, String> {
+        self.execute_checked_on(validator, confirmer, &RealFs)
+    }
+
+    /// Same as `execute_checked`, but validates and executes against `fs`.
+    pub fn execute_checked_on<F: FileSystem, C: Confirmer>(
+        &self,
+        validator: &SafetyValidator,
+        confirmer: &C,
+        fs: &F,
+    ) -> Result<String, String> {
+        match self.validate_on(validator, fs)? {
+            SafetyLevel::Blocked => Err(format!(
+                "Operation blocked: {} is a protected system path",
+                self.target_path.display()
+            )),
+            SafetyLevel::NeedsConfirmation => {
+                let reason = format!(
+                    "{:?} on {} needs confirmation",
+                    self.action_type,
+                    self.target_path.display()
+                );
+                if confirmer.confirm(self, &reason) {
+                    self.execute_on(fs)
+                } else {
+                    Err(format!(
+                        "Operation cancelled: {} was not approved",
+                        self.target_path.display()
+                    ))
+                }
+            }
+            SafetyLevel::Safe => self.execute_on(fs),
+        }
+    }
+
+    /// Execute the filesystem action against any `FileSystem` backend, e.g. `RealFs`
+    /// in production or `InMemoryFs` in tests.
+    pub fn execute_on<F: FileSystem>(&self, fs: &F) -> Result<String, String> {
+        self.execute_on_with_progress(fs, &mut |_, _, _| {})
+    }
+
+    /// Same as `execute_on`, but `progress` is called as a `MoveFolder`/`CopyFolder`
+    /// recurses, reporting bytes transferred so far. Other action types ignore it.
+    pub fn execute_on_with_progress<F: FileSystem>(
+        &self,
+        fs: &F,
+        progress: &mut ProgressCallback,
+    ) -> Result<String, String> {
         match self.action_type {
-            ActionType::CreateFile => self.create_file(),
-            ActionType::CreateFolder => self.create_folder(),
-            ActionType::DeleteFile => self.delete_file(),
-            ActionType::DeleteFolder => self.delete_folder(),
-            ActionType::MoveFile => self.move_file(),
-            ActionType::CopyFile => self.copy_file(),
+            ActionType::CreateFile => self.create_file(fs),
+            ActionType::CreateFolder => self.create_folder(fs),
+            ActionType::DeleteFile => self.delete_file(fs),
+            ActionType::DeleteFolder => self.delete_folder(fs),
+            ActionType::MoveFile => self.move_file(fs),
+            ActionType::CopyFile => self.copy_file(fs),
+            ActionType::CopyFolder => self.copy_folder(fs, progress),
+            ActionType::MoveFolder => self.move_folder(fs, progress),
         }
     }
 
-    fn create_file(&self) -> Result<String, String> {
+    fn create_file<F: FileSystem>(&self, fs: &F) -> Result<String, String> {
         // Ensure parent directory exists
         if let Some(parent) = self.target_path.parent() {
-            fs::create_dir_all(parent)
+            fs.create_dir_all(parent)
                 .map_err(|e| format!("Failed to create parent directory: {}", e))?;
         }
 
         // Create file with optional content
         let content = self.content.as_deref().unwrap_or("");
-        fs::write(&self.target_path, content)
+        fs.write(&self.target_path, content.as_bytes())
             .map_err(|e| format!("Failed to create file: {}", e))?;
 
+        if let Some(mode) = self.mode {
+            fs.set_mode(&self.target_path, mode)
+                .map_err(|e| format!("Failed to set file mode: {}", e))?;
+        }
+
         Ok(format!("Created file: {}", self.target_path.display()))
     }
 
-    fn create_folder(&self) -> Result<String, String> {
-        fs::create_dir_all(&self.target_path)
+    fn create_folder<F: FileSystem>(&self, fs: &F) -> Result<String, String> {
+        fs.create_dir_all(&self.target_path)
             .map_err(|e| format!("Failed to create folder: {}", e))?;
 
         Ok(format!("Created folder: {}", self.target_path.display()))
     }
 
-    fn delete_file(&self) -> Result<String, String> {
-        if !self.target_path.exists() {
+    fn delete_file<F: FileSystem>(&self, fs: &F) -> Result<String, String> {
+        if !fs.exists(&self.target_path) {
             return Err(format!("File not found: {}", self.target_path.display()));
         }
 
-        if !self.target_path.is_file() {
+        if !fs.is_file(&self.target_path) {
             return Err(format!("Not a file: {}", self.target_path.display()));
         }
 
-        fs::remove_file(&self.target_path)
+        fs.remove_file(&self.target_path)
             .map_err(|e| format!("Failed to delete file: {}", e))?;
 
         Ok(format!("Deleted file: {}", self.target_path.display()))
     }
 
-    fn delete_folder(&self) -> Result<String, String> {
-        if !self.target_path.exists() {
+    fn delete_folder<F: FileSystem>(&self, fs: &F) -> Result<String, String> {
+        if !fs.exists(&self.target_path) {
             return Err(format!("Folder not found: {}", self.target_path.display()));
         }
 
-        if !self.target_path.is_dir() {
+        if !fs.is_dir(&self.target_path) {
             return Err(format!("Not a folder: {}", self.target_path.display()));
         }
 
-        fs::remove_dir_all(&self.target_path)
+        fs.remove_dir_all(&self.target_path)
             .map_err(|e| format!("Failed to delete folder: {}", e))?;
 
         Ok(format!("Deleted folder: {}", self.target_path.display()))
     }
 
-    fn move_file(&self) -> Result<String, String> {
+    fn move_file<F: FileSystem>(&self, fs: &F) -> Result<String, String> {
         let dest = self.destination_path.as_ref()
             .ok_or("Destination path required for move operation")?;
 
-        if !self.target_path.exists() {
+        if !fs.exists(&self.target_path) {
             return Err(format!("Source file not found: {}", self.target_path.display()));
         }
 
-        fs::rename(&self.target_path, dest)
+        fs.rename_or_copy(&self.target_path, dest)
             .map_err(|e| format!("Failed to move file: {}", e))?;
 
         Ok(format!(
@@ -149,28 +234,121 @@ impl FileSystemAction {
         ))
     }
 
-    fn copy_file(&self) -> Result<String, String> {
+    fn copy_file<F: FileSystem>(&self, fs: &F) -> Result<String, String> {
         let dest = self.destination_path.as_ref()
             .ok_or("Destination path required for copy operation")?;
 
-        if !self.target_path.exists() {
+        if !fs.exists(&self.target_path) {
             return Err(format!("Source file not found: {}", self.target_path.display()));
         }
 
-        fs::copy(&self.target_path, dest)
+        fs.copy(&self.target_path, dest)
             .map_err(|e| format!("Failed to copy file: {}", e))?;
 
+        if let Some(mode) = self.mode {
+            fs.set_mode(dest, mode)
+                .map_err(|e| format!("Failed to set file mode: {}", e))?;
+        }
+
+        Ok(format!(
+            "Copied {} to {}",
+            self.target_path.display(),
+            dest.display()
+        ))
+    }
+
+    fn copy_folder<F: FileSystem>(&self, fs: &F, progress: &mut ProgressCallback) -> Result<String, String> {
+        let dest = self.destination_path.as_ref()
+            .ok_or("Destination path required for folder copy operation")?;
+
+        if !fs.is_dir(&self.target_path) {
+            return Err(format!("Source folder not found: {}", self.target_path.display()));
+        }
+
+        copy_folder_recursive(fs, &self.target_path, dest, progress)
+            .map_err(|e| format!("Failed to copy folder: {}", e))?;
+
         Ok(format!(
             "Copied {} to {}",
             self.target_path.display(),
             dest.display()
         ))
     }
+
+    fn move_folder<F: FileSystem>(&self, fs: &F, progress: &mut ProgressCallback) -> Result<String, String> {
+        let dest = self.destination_path.as_ref()
+            .ok_or("Destination path required for folder move operation")?;
+
+        if !fs.is_dir(&self.target_path) {
+            return Err(format!("Source folder not found: {}", self.target_path.display()));
+        }
+
+        copy_folder_recursive(fs, &self.target_path, dest, progress)
+            .map_err(|e| format!("Failed to move folder: {}", e))?;
+        fs.remove_dir_all(&self.target_path)
+            .map_err(|e| format!("Failed to remove source folder after move: {}", e))?;
+
+        Ok(format!(
+            "Moved {} to {}",
+            self.target_path.display(),
+            dest.display()
+        ))
+    }
+}
+
+/// Depth-first listing of every file under `root` (directories are descended into,
+/// not included themselves).
+fn walk_files<F: FileSystem>(fs: &F, root: &Path) -> std::io::Result<Vec<PathBuf>> {
+    let mut files = Vec::new();
+    let mut stack = vec![root.to_path_buf()];
+
+    while let Some(dir) = stack.pop() {
+        for entry in fs.read_dir(&dir)? {
+            if fs.is_dir(&entry) {
+                stack.push(entry);
+            } else {
+                files.push(entry);
+            }
+        }
+    }
+
+    Ok(files)
+}
+
+/// Recursively copy every file under `src` to the matching path under `dest`,
+/// reporting cumulative bytes copied via `progress` after each file. Totals are
+/// computed with an initial walk pass so `progress` always sees the true total.
+fn copy_folder_recursive<F: FileSystem>(
+    fs: &F,
+    src: &Path,
+    dest: &Path,
+    progress: &mut ProgressCallback,
+) -> std::io::Result<()> {
+    let files = walk_files(fs, src)?;
+    let sizes: Vec<u64> = files.iter().map(|f| fs.file_size(f).unwrap_or(0)).collect();
+    let total: u64 = sizes.iter().sum();
+    let mut done = 0u64;
+
+    fs.create_dir_all(dest)?;
+
+    for (file, size) in files.iter().zip(sizes.iter()) {
+        let relative = file.strip_prefix(src).unwrap_or(file);
+        let dest_file = dest.join(relative);
+        if let Some(parent) = dest_file.parent() {
+            fs.create_dir_all(parent)?;
+        }
+        fs.copy(file, &dest_file)?;
+        done += size;
+        progress(done, total, file);
+    }
+
+    Ok(())
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use super::super::fs_backend::InMemoryFs;
     use std::env;
 
     #[test]
@@ -196,4 +374,94 @@ mod tests {
         assert!(delete_action.execute().is_ok());
         assert!(!test_file.exists());
     }
+
+    #[test]
+    fn test_create_move_delete_against_in_memory_fs() {
+        let fs = InMemoryFs::new();
+        let src = PathBuf::from("/virtual/a.txt");
+        let dest = PathBuf::from("/virtual/b.txt");
+
+        let create = FileSystemAction::new(ActionType::CreateFile, src.clone())
+            .with_content("hello".to_string());
+        assert!(create.execute_on(&fs).is_ok());
+        assert!(fs.exists(&src));
+
+        let mv = FileSystemAction::new(ActionType::MoveFile, src.clone()).with_destination(dest.clone());
+        assert!(mv.execute_on(&fs).is_ok());
+        assert!(!fs.exists(&src));
+        assert!(fs.exists(&dest));
+
+        let delete = FileSystemAction::new(ActionType::DeleteFile, dest.clone());
+        assert!(delete.execute_on(&fs).is_ok());
+        assert!(!fs.exists(&dest));
+    }
+
+    #[test]
+    fn test_execute_checked_respects_confirmer() {
+        use super::super::confirm::{AutoApprove, AutoDeny};
+
+        let fs = InMemoryFs::new();
+        let validator = SafetyValidator::new();
+        let target = PathBuf::from("/some/unclassified/path/test.txt");
+
+        let denied = FileSystemAction::new(ActionType::CreateFile, target.clone())
+            .with_content("hello".to_string());
+        let result = denied.execute_checked_on(&validator, &AutoDeny, &fs);
+        assert!(result.is_err());
+        assert!(!fs.exists(&target));
+
+        let approved = FileSystemAction::new(ActionType::CreateFile, target.clone())
+            .with_content("hello".to_string());
+        let result = approved.execute_checked_on(&validator, &AutoApprove, &fs);
+        assert!(result.is_ok());
+        assert!(fs.exists(&target));
+    }
+
+    #[test]
+    fn test_copy_folder_recursive_reports_progress() {
+        let fs = InMemoryFs::new();
+        let src_a = PathBuf::from("/virtual/src/a.txt");
+        let src_b = PathBuf::from("/virtual/src/nested/b.txt");
+
+        FileSystemAction::new(ActionType::CreateFile, src_a.clone())
+            .with_content("aaa".to_string())
+            .execute_on(&fs)
+            .unwrap();
+        FileSystemAction::new(ActionType::CreateFile, src_b.clone())
+            .with_content("bbbb".to_string())
+            .execute_on(&fs)
+            .unwrap();
+
+        let mut last_progress = (0u64, 0u64);
+        let copy = FileSystemAction::new(ActionType::CopyFolder, PathBuf::from("/virtual/src"))
+            .with_destination(PathBuf::from("/virtual/dest"));
+        let result = copy.execute_on_with_progress(&fs, &mut |done, total, _path| {
+            last_progress = (done, total);
+        });
+
+        assert!(result.is_ok());
+        assert_eq!(last_progress, (7, 7));
+        assert!(fs.exists(&PathBuf::from("/virtual/dest/a.txt")));
+        assert!(fs.exists(&PathBuf::from("/virtual/dest/nested/b.txt")));
+        // Source is untouched by a copy
+        assert!(fs.exists(&src_a));
+    }
+
+    #[test]
+    fn test_move_folder_recursive_removes_source() {
+        let fs = InMemoryFs::new();
+        let src_a = PathBuf::from("/virtual/movesrc/a.txt");
+
+        FileSystemAction::new(ActionType::CreateFile, src_a.clone())
+            .with_content("aaa".to_string())
+            .execute_on(&fs)
+            .unwrap();
+
+        let mv = FileSystemAction::new(ActionType::MoveFolder, PathBuf::from("/virtual/movesrc"))
+            .with_destination(PathBuf::from("/virtual/movedest"));
+        assert!(mv.execute_on(&fs).is_ok());
+
+        assert!(!fs.exists(&src_a));
+        assert!(fs.exists(&PathBuf::from("/virtual/movedest/a.txt")));
+    }
 }
@@ -0,0 +1,306 @@
+use std::collections::{HashMap, HashSet};
+use std::io;
+use std::path::{Component, Path, PathBuf};
+use std::sync::Mutex;
+use uuid::Uuid;
+
+/// Filesystem operations used by `FileSystemAction`, abstracted so the action and
+/// safety layers can run against the real disk or an in-memory fake. This is the
+/// std_fs/in_memory_fs split: it lets the test suite exercise create/delete/move/copy
+/// and every `SafetyLevel` interaction deterministically, without touching the real
+/// disk or relying on `env::temp_dir()`.
+pub trait FileSystem {
+    fn write(&self, path: &Path, content: &[u8]) -> io::Result<()>;
+    fn read(&self, path: &Path) -> io::Result<Vec<u8>>;
+    fn create_dir_all(&self, path: &Path) -> io::Result<()>;
+    fn remove_file(&self, path: &Path) -> io::Result<()>;
+    fn remove_dir_all(&self, path: &Path) -> io::Result<()>;
+    fn rename(&self, from: &Path, to: &Path) -> io::Result<()>;
+    fn copy(&self, from: &Path, to: &Path) -> io::Result<u64>;
+    fn exists(&self, path: &Path) -> bool;
+    fn is_file(&self, path: &Path) -> bool;
+    fn is_dir(&self, path: &Path) -> bool;
+
+    /// Immediate children of a directory (files and subdirectories), used to walk a
+    /// folder for recursive copy/move.
+    fn read_dir(&self, path: &Path) -> io::Result<Vec<PathBuf>>;
+
+    /// Size in bytes of a file, used to total up recursive copy/move progress.
+    fn file_size(&self, path: &Path) -> io::Result<u64>;
+
+    /// Apply a unix permission mode to an existing file. Backends that don't model
+    /// permissions (e.g. the in-memory fake) can leave this as a no-op.
+    fn set_mode(&self, _path: &Path, _mode: u32) -> io::Result<()> {
+        Ok(())
+    }
+
+    /// Resolve `path` for `SafetyValidator` classification, following symlinks and
+    /// `..` components the way a real disk would. Backends that don't model a real,
+    /// symlink-capable filesystem (e.g. the in-memory fake) have nothing to resolve,
+    /// so the default just trusts the path as given.
+    fn resolve_for_safety(&self, path: &Path) -> PathBuf {
+        path.to_path_buf()
+    }
+
+    /// Rename `from` to `to`, falling back to copy-then-delete when `rename` fails
+    /// because the two paths are on different devices (`EXDEV`/`ERROR_NOT_SAME_DEVICE`).
+    fn rename_or_copy(&self, from: &Path, to: &Path) -> io::Result<()> {
+        match self.rename(from, to) {
+            Ok(()) => Ok(()),
+            Err(e) if is_cross_device(&e) => {
+                self.copy(from, to)?;
+                self.remove_file(from)
+            }
+            Err(e) => Err(e),
+        }
+    }
+}
+
+/// The real, disk-backed filesystem.
+pub struct RealFs;
+
+impl FileSystem for RealFs {
+    fn write(&self, path: &Path, content: &[u8]) -> io::Result<()> {
+        atomic_write(path, content)
+    }
+
+    fn read(&self, path: &Path) -> io::Result<Vec<u8>> {
+        std::fs::read(path)
+    }
+
+    fn create_dir_all(&self, path: &Path) -> io::Result<()> {
+        std::fs::create_dir_all(path)
+    }
+
+    fn remove_file(&self, path: &Path) -> io::Result<()> {
+        std::fs::remove_file(path)
+    }
+
+    fn remove_dir_all(&self, path: &Path) -> io::Result<()> {
+        std::fs::remove_dir_all(path)
+    }
+
+    fn rename(&self, from: &Path, to: &Path) -> io::Result<()> {
+        std::fs::rename(from, to)
+    }
+
+    fn copy(&self, from: &Path, to: &Path) -> io::Result<u64> {
+        std::fs::copy(from, to)
+    }
+
+    fn exists(&self, path: &Path) -> bool {
+        path.exists()
+    }
+
+    fn is_file(&self, path: &Path) -> bool {
+        path.is_file()
+    }
+
+    fn is_dir(&self, path: &Path) -> bool {
+        path.is_dir()
+    }
+
+    fn read_dir(&self, path: &Path) -> io::Result<Vec<PathBuf>> {
+        std::fs::read_dir(path)?
+            .map(|entry| entry.map(|e| e.path()))
+            .collect()
+    }
+
+    fn file_size(&self, path: &Path) -> io::Result<u64> {
+        Ok(std::fs::metadata(path)?.len())
+    }
+
+    #[cfg(unix)]
+    fn set_mode(&self, path: &Path, mode: u32) -> io::Result<()> {
+        use std::os::unix::fs::PermissionsExt;
+        std::fs::set_permissions(path, std::fs::Permissions::from_mode(mode))
+    }
+
+    fn resolve_for_safety(&self, path: &Path) -> PathBuf {
+        resolve_containment(path).unwrap_or_else(|| path.to_path_buf())
+    }
+}
+
+/// Resolve `path` to a fully symlink-and-`..`-free absolute path, or `None` if
+/// containment can't be proven (a component beyond the last existing ancestor tries
+/// to climb with `..`, or the existing prefix itself can't be canonicalized at all).
+///
+/// Walks up from `path` to the nearest existing ancestor (canonicalizing it, which
+/// follows symlinks and resolves any `..` within the *existing* part of the path),
+/// then re-appends the remaining, not-yet-existing components.
+pub(crate) fn resolve_containment(path: &Path) -> Option<PathBuf> {
+    let mut existing = path;
+    let mut suffix: Vec<PathBuf> = Vec::new();
+
+    loop {
+        if let Ok(canon) = existing.canonicalize() {
+            let mut result = canon;
+            for part in suffix.into_iter().rev() {
+                result.push(part);
+            }
+            return Some(result);
+        }
+
+        match existing.components().next_back() {
+            Some(Component::Normal(part)) => suffix.push(PathBuf::from(part)),
+            // A `..` (or `.`, root, prefix) beyond the last real ancestor can't be
+            // resolved without the filesystem confirming what it climbs past.
+            _ => return None,
+        }
+
+        existing = existing.parent()?;
+    }
+}
+
+/// Write `content` to `target` via a sibling temp file + rename, falling back to a
+/// direct write if the rename can't cross devices. See `FileSystemAction::create_file`.
+fn atomic_write(target: &Path, content: &[u8]) -> io::Result<()> {
+    use std::io::Write;
+
+    let suffix = Uuid::new_v4().simple().to_string()[..8].to_string();
+    let file_name = target
+        .file_name()
+        .map(|n| n.to_string_lossy().into_owned())
+        .unwrap_or_else(|| "fluffy".to_string());
+    let tmp_path = target.with_file_name(format!("{}.{}.tmp", file_name, suffix));
+
+    let mut tmp_file = std::fs::File::create(&tmp_path)?;
+    tmp_file.write_all(content)?;
+    tmp_file.sync_all()?;
+    drop(tmp_file);
+
+    match std::fs::rename(&tmp_path, target) {
+        Ok(()) => Ok(()),
+        Err(e) if is_cross_device(&e) => {
+            let result = std::fs::write(target, content);
+            let _ = std::fs::remove_file(&tmp_path);
+            result
+        }
+        Err(e) => {
+            let _ = std::fs::remove_file(&tmp_path);
+            Err(e)
+        }
+    }
+}
+
+#[cfg(unix)]
+fn is_cross_device(e: &io::Error) -> bool {
+    e.raw_os_error() == Some(18) // EXDEV
+}
+
+#[cfg(windows)]
+fn is_cross_device(e: &io::Error) -> bool {
+    e.raw_os_error() == Some(17) // ERROR_NOT_SAME_DEVICE
+}
+
+/// In-memory filesystem fake: files live in a `HashMap<PathBuf, Vec<u8>>`, directories
+/// in a parallel set, so higher layers can be exercised deterministically in tests.
+#[derive(Default)]
+pub struct InMemoryFs {
+    files: Mutex<HashMap<PathBuf, Vec<u8>>>,
+    dirs: Mutex<HashSet<PathBuf>>,
+}
+
+impl InMemoryFs {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+fn not_found(what: &str) -> io::Error {
+    io::Error::new(io::ErrorKind::NotFound, what.to_string())
+}
+
+impl FileSystem for InMemoryFs {
+    fn write(&self, path: &Path, content: &[u8]) -> io::Result<()> {
+        if let Some(parent) = path.parent() {
+            self.dirs.lock().unwrap().insert(parent.to_path_buf());
+        }
+        self.files.lock().unwrap().insert(path.to_path_buf(), content.to_vec());
+        Ok(())
+    }
+
+    fn read(&self, path: &Path) -> io::Result<Vec<u8>> {
+        self.files
+            .lock()
+            .unwrap()
+            .get(path)
+            .cloned()
+            .ok_or_else(|| not_found("file not found"))
+    }
+
+    fn create_dir_all(&self, path: &Path) -> io::Result<()> {
+        self.dirs.lock().unwrap().insert(path.to_path_buf());
+        Ok(())
+    }
+
+    fn remove_file(&self, path: &Path) -> io::Result<()> {
+        self.files
+            .lock()
+            .unwrap()
+            .remove(path)
+            .map(|_| ())
+            .ok_or_else(|| not_found("file not found"))
+    }
+
+    fn remove_dir_all(&self, path: &Path) -> io::Result<()> {
+        self.files.lock().unwrap().retain(|p, _| !p.starts_with(path));
+        let mut dirs = self.dirs.lock().unwrap();
+        dirs.retain(|d| !d.starts_with(path) || d == path);
+        dirs.remove(path);
+        Ok(())
+    }
+
+    fn rename(&self, from: &Path, to: &Path) -> io::Result<()> {
+        let mut files = self.files.lock().unwrap();
+        let data = files.remove(from).ok_or_else(|| not_found("source not found"))?;
+        files.insert(to.to_path_buf(), data);
+        Ok(())
+    }
+
+    fn copy(&self, from: &Path, to: &Path) -> io::Result<u64> {
+        let data = self
+            .files
+            .lock()
+            .unwrap()
+            .get(from)
+            .cloned()
+            .ok_or_else(|| not_found("source not found"))?;
+        let len = data.len() as u64;
+        self.files.lock().unwrap().insert(to.to_path_buf(), data);
+        Ok(len)
+    }
+
+    fn exists(&self, path: &Path) -> bool {
+        self.files.lock().unwrap().contains_key(path) || self.dirs.lock().unwrap().contains(path)
+    }
+
+    fn is_file(&self, path: &Path) -> bool {
+        self.files.lock().unwrap().contains_key(path)
+    }
+
+    fn is_dir(&self, path: &Path) -> bool {
+        self.dirs.lock().unwrap().contains(path)
+    }
+
+    fn read_dir(&self, path: &Path) -> io::Result<Vec<PathBuf>> {
+        let files = self.files.lock().unwrap();
+        let dirs = self.dirs.lock().unwrap();
+        let children = files
+            .keys()
+            .chain(dirs.iter())
+            .filter(|p| p.parent() == Some(path))
+            .cloned()
+            .collect();
+        Ok(children)
+    }
+
+    fn file_size(&self, path: &Path) -> io::Result<u64> {
+        self.files
+            .lock()
+            .unwrap()
+            .get(path)
+            .map(|data| data.len() as u64)
+            .ok_or_else(|| not_found("file not found"))
+    }
+}
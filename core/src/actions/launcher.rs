@@ -1,6 +1,9 @@
 use std::collections::HashMap;
 use std::path::PathBuf;
-use std::process::Command;
+use std::process::{Child, Command};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+use once_cell::sync::Lazy;
 use serde::{Deserialize, Serialize};
 
 #[cfg(windows)]
@@ -13,16 +16,94 @@ pub struct AppInfo {
     pub name: String,
     pub path: PathBuf,
     pub display_name: String,
+    /// Remaining `Exec` tokens after the binary, field codes (`%f`/`%u`/`%F`/`%U`) stripped.
+    #[serde(default)]
+    pub exec_args: Vec<String>,
+    #[serde(default)]
+    pub icon: Option<String>,
+    /// Whether the `.desktop` entry declared `Terminal=true`, meaning it must be run
+    /// inside a terminal emulator rather than launched directly.
+    #[serde(default)]
+    pub terminal: bool,
+}
+
+// Pathlist env vars that accumulate runtime-injected directories (AppImage/snap/flatpak
+// bundling, mainly) and so need to be rebuilt from a clean snapshot before spawning a
+// child, rather than inherited as-is.
+#[cfg(not(windows))]
+const PATHLIST_VARS: &[&str] = &[
+    "PATH",
+    "LD_LIBRARY_PATH",
+    "GST_PLUGIN_SYSTEM_PATH",
+    "GTK_PATH",
+    "GIO_MODULE_DIR",
+    "PYTHONPATH",
+    "XDG_DATA_DIRS",
+];
+
+// Vars the packaging runtime sets that have no meaning for an external process at all;
+// dropped entirely rather than normalized.
+#[cfg(not(windows))]
+const RUNTIME_ONLY_VARS: &[&str] = &["APPDIR", "APPIMAGE", "OWD", "LD_PRELOAD"];
+
+/// A process Fluffy itself spawned, kept alive long enough to report on or kill it.
+/// Shared across every `AppLauncher` instance (each command handler builds its own),
+/// since the tracked process outlives any single instance.
+struct TrackedProc {
+    child: Child,
+    display_name: String,
+    launched_at: Instant,
+}
+
+static TRACKED: Lazy<Mutex<HashMap<u32, TrackedProc>>> = Lazy::new(|| Mutex::new(HashMap::new()));
+
+/// Whether `pid` is a process Fluffy itself launched and is still tracking. Used by
+/// `permissions::policy` to grant killing our own children less friction than killing
+/// an arbitrary system pid.
+pub fn is_tracked(pid: u32) -> bool {
+    TRACKED.lock().unwrap().contains_key(&pid)
+}
+
+/// Sweep `TRACKED` for processes that have already exited and drop them, so a launched
+/// app that's never explicitly polled or killed doesn't leak its registry entry (and,
+/// on Unix, its zombie) forever. Meant to be called periodically from the main tick
+/// loop rather than relying on `running`/`kill_by_name` being called for every pid.
+pub fn reap_finished() {
+    TRACKED.lock().unwrap().retain(|_, proc| !matches!(proc.child.try_wait(), Ok(Some(_)) | Err(_)));
 }
 
 pub struct AppLauncher {
     installed_apps: HashMap<String, AppInfo>,
+    // Clean values of `PATHLIST_VARS` captured before the runtime had a chance to
+    // inject its own directories, used to sanitize the environment of launched apps.
+    #[cfg(not(windows))]
+    env_snapshot: HashMap<String, String>,
 }
 
 impl AppLauncher {
     pub fn new() -> Self {
         let installed_apps = Self::scan_installed_apps();
-        Self { installed_apps }
+
+        #[cfg(not(windows))]
+        {
+            Self {
+                installed_apps,
+                env_snapshot: Self::capture_env_snapshot(),
+            }
+        }
+
+        #[cfg(windows)]
+        {
+            Self { installed_apps }
+        }
+    }
+
+    #[cfg(not(windows))]
+    fn capture_env_snapshot() -> HashMap<String, String> {
+        PATHLIST_VARS
+            .iter()
+            .filter_map(|&name| std::env::var(name).ok().map(|value| (name.to_string(), value)))
+            .collect()
     }
 
     /// Scan for installed applications
@@ -42,6 +123,10 @@ impl AppLauncher {
         {
             // For Linux/Mac, scan common application directories
             Self::scan_unix_apps(&mut apps);
+
+            // Then layer .desktop entries on top - these carry icon/terminal metadata
+            // a raw executable scan can't see, so let them overwrite bare-binary entries.
+            Self::scan_desktop_entries(&mut apps);
         }
 
         apps
@@ -72,6 +157,9 @@ impl AppLauncher {
                                     name: name_lower,
                                     path,
                                     display_name,
+                                    exec_args: Vec::new(),
+                                    icon: None,
+                                    terminal: false,
                                 },
                             );
                         }
@@ -102,6 +190,9 @@ impl AppLauncher {
                         name: name.to_string(),
                         path,
                         display_name: display_name.to_string(),
+                        exec_args: Vec::new(),
+                        icon: None,
+                        terminal: false,
                     },
                 );
             }
@@ -130,6 +221,9 @@ impl AppLauncher {
                                     name: name_lower.clone(),
                                     path: path.clone(),
                                     display_name: name.to_string(),
+                                    exec_args: Vec::new(),
+                                    icon: None,
+                                    terminal: false,
                                 },
                             );
                         }
@@ -139,6 +233,51 @@ impl AppLauncher {
         }
     }
 
+    /// Scan `.desktop` entries under `applications/` in `$XDG_DATA_DIRS` plus
+    /// `~/.local/share`, per the XDG Desktop Entry Specification. These typically carry
+    /// richer metadata (icon, whether they need a terminal) than a bare executable scan,
+    /// and `Exec` often names a bare binary that must resolve via `$PATH`.
+    #[cfg(not(windows))]
+    fn scan_desktop_entries(apps: &mut HashMap<String, AppInfo>) {
+        for dir in Self::desktop_entry_dirs() {
+            let applications_dir = dir.join("applications");
+            let Ok(entries) = std::fs::read_dir(&applications_dir) else {
+                continue;
+            };
+
+            for entry in entries.filter_map(Result::ok) {
+                let path = entry.path();
+                if path.extension().and_then(|e| e.to_str()) != Some("desktop") {
+                    continue;
+                }
+
+                let Ok(contents) = std::fs::read_to_string(&path) else {
+                    continue;
+                };
+
+                if let Some(app) = parse_desktop_entry(&contents) {
+                    let name_lower = app.name.to_lowercase();
+                    apps.insert(name_lower, app);
+                }
+            }
+        }
+    }
+
+    #[cfg(not(windows))]
+    fn desktop_entry_dirs() -> Vec<PathBuf> {
+        let mut dirs = Vec::new();
+
+        if let Some(home_share) = dirs::data_dir() {
+            dirs.push(home_share);
+        }
+
+        let xdg_data_dirs = std::env::var("XDG_DATA_DIRS")
+            .unwrap_or_else(|_| "/usr/local/share:/usr/share".to_string());
+        dirs.extend(xdg_data_dirs.split(':').filter(|d| !d.is_empty()).map(PathBuf::from));
+
+        dirs
+    }
+
     /// Find an application by name (fuzzy matching)
     pub fn find_app(&self, query: &str) -> Option<&AppInfo> {
         let query_lower = query.to_lowercase();
@@ -148,10 +287,27 @@ impl AppLauncher {
             return Some(app);
         }
 
-        // Fuzzy match - find apps containing the query
-        self.installed_apps
+        // Otherwise the best-scoring fuzzy subsequence match, if any
+        self.find_apps_ranked(query, 1).into_iter().next().map(|(app, _)| app)
+    }
+
+    /// Fuzzy-match `query` against every installed app's `name`/`display_name` using a
+    /// subsequence scorer (see `score_subsequence`), returning up to `limit` results
+    /// sorted best-first. Lets a UI show a disambiguation list when scores are close,
+    /// rather than silently picking whichever match `find_app` happened to see first.
+    pub fn find_apps_ranked(&self, query: &str, limit: usize) -> Vec<(&AppInfo, i32)> {
+        let mut scored: Vec<(&AppInfo, i32)> = self
+            .installed_apps
             .values()
-            .find(|app| app.name.contains(&query_lower) || app.display_name.to_lowercase().contains(&query_lower))
+            .filter_map(|app| {
+                let best = score_subsequence(query, &app.name).max(score_subsequence(query, &app.display_name));
+                best.map(|score| (app, score))
+            })
+            .collect();
+
+        scored.sort_by(|a, b| b.1.cmp(&a.1));
+        scored.truncate(limit);
+        scored
     }
 
     /// Launch an application
@@ -159,33 +315,149 @@ impl AppLauncher {
         let app = self.find_app(app_name)
             .ok_or_else(|| format!("Application '{}' not found", app_name))?;
 
-        self.launch_path(&app.path, &app.display_name)
+        self.launch_path(app)
+    }
+
+    /// Launch application
+    pub fn launch_path(&self, app: &AppInfo) -> Result<String, String> {
+        self.spawn(app, &[])
     }
 
-    /// Launch application by path
-    pub fn launch_path(&self, path: &PathBuf, display_name: &str) -> Result<String, String> {
-        if !path.exists() {
-            return Err(format!("Application path not found: {}", path.display()));
+    /// Open `file` with a chosen application, resolved the same way `launch` resolves
+    /// `app_name`. Complements `OpenPath` (which only uses the OS default handler) by
+    /// letting the caller pick the application explicitly.
+    pub fn open_with(&self, file: &std::path::Path, app_query: &str) -> Result<String, String> {
+        let app = self.find_app(app_query)
+            .ok_or_else(|| format!("Application '{}' not found", app_query))?;
+
+        self.spawn(app, &[file.as_os_str()])
+    }
+
+    fn spawn(&self, app: &AppInfo, extra_args: &[&std::ffi::OsStr]) -> Result<String, String> {
+        let display_name = &app.display_name;
+
+        // Absolute paths must exist up front; bare names (e.g. from a `.desktop` entry's
+        // `Exec=firefox %u`) are resolved against `$PATH` by `Command` itself at spawn time.
+        if app.path.is_absolute() && !app.path.exists() {
+            return Err(format!("Application path not found: {}", app.path.display()));
         }
 
+        let child;
+
         #[cfg(windows)]
         {
-            Command::new("cmd")
-                .args(&["/C", "start", "", path.to_str().unwrap()])
+            let mut args = vec!["/C".to_string(), "start".to_string(), "".to_string(), app.path.to_str().unwrap().to_string()];
+            args.extend(extra_args.iter().map(|a| a.to_string_lossy().into_owned()));
+            child = Command::new("cmd")
+                .args(&args)
                 .spawn()
                 .map_err(|e| format!("Failed to launch {}: {}", display_name, e))?;
         }
 
         #[cfg(not(windows))]
         {
-            Command::new(path)
+            let mut command = if app.terminal {
+                let mut c = Command::new(terminal_emulator());
+                c.arg("-e").arg(&app.path).args(&app.exec_args).args(extra_args);
+                c
+            } else {
+                let mut c = Command::new(&app.path);
+                c.args(&app.exec_args).args(extra_args);
+                c
+            };
+
+            // Rebuild each pathlist var from the clean startup snapshot so a launched
+            // app doesn't inherit Fluffy's own packaging-runtime directories.
+            for &name in PATHLIST_VARS {
+                let current = std::env::var(name).unwrap_or_default();
+                let snapshot = self.env_snapshot.get(name).map(String::as_str).unwrap_or("");
+                let normalized = normalize_pathlist(&current, snapshot);
+                if normalized.is_empty() {
+                    command.env_remove(name);
+                } else {
+                    command.env(name, normalized);
+                }
+            }
+
+            for &name in RUNTIME_ONLY_VARS {
+                command.env_remove(name);
+            }
+
+            child = command
                 .spawn()
                 .map_err(|e| format!("Failed to launch {}: {}", display_name, e))?;
         }
 
+        let pid = child.id();
+        TRACKED.lock().unwrap().insert(
+            pid,
+            TrackedProc {
+                child,
+                display_name: display_name.clone(),
+                launched_at: Instant::now(),
+            },
+        );
+
         Ok(format!("Launched {}", display_name))
     }
 
+    /// Non-blocking check for whether a tracked process is still alive. Reaps and
+    /// forgets it from the registry once it's exited.
+    pub fn running(&self, pid: u32) -> bool {
+        let mut tracked = TRACKED.lock().unwrap();
+        let Some(proc) = tracked.get_mut(&pid) else {
+            return false;
+        };
+
+        match proc.child.try_wait() {
+            Ok(Some(_)) | Err(_) => {
+                tracked.remove(&pid);
+                false
+            }
+            Ok(None) => true,
+        }
+    }
+
+    /// How long ago a still-tracked process was launched, or `None` if it isn't tracked.
+    pub fn uptime(&self, pid: u32) -> Option<Duration> {
+        TRACKED.lock().unwrap().get(&pid).map(|proc| proc.launched_at.elapsed())
+    }
+
+    /// Poll a tracked process until it exits or `timeout` elapses. Returns true if it
+    /// exited within the timeout.
+    pub fn wait(&self, pid: u32, timeout: Duration) -> bool {
+        let deadline = Instant::now() + timeout;
+        loop {
+            if !self.running(pid) {
+                return true;
+            }
+            if Instant::now() >= deadline {
+                return false;
+            }
+            std::thread::sleep(Duration::from_millis(100));
+        }
+    }
+
+    /// Kill a process Fluffy itself launched, found by fuzzy-matching its display name
+    /// against the tracked registry (e.g. "close chrome"). Unlike `KillProcess`, this
+    /// never touches an arbitrary system pid the assistant didn't start.
+    pub fn kill_by_name(&self, query: &str) -> Result<String, String> {
+        let query_lower = query.to_lowercase();
+        let mut tracked = TRACKED.lock().unwrap();
+
+        let pid = tracked
+            .iter()
+            .find(|(_, proc)| proc.display_name.to_lowercase().contains(&query_lower))
+            .map(|(&pid, _)| pid)
+            .ok_or_else(|| format!("No running process matching '{}'", query))?;
+
+        let mut proc = tracked.remove(&pid).unwrap();
+        proc.child.kill().map_err(|e| format!("Failed to kill {}: {}", proc.display_name, e))?;
+        let _ = proc.child.wait();
+
+        Ok(format!("Killed {}", proc.display_name))
+    }
+
     /// Get list of all installed apps
     pub fn list_apps(&self) -> Vec<&AppInfo> {
         self.installed_apps.values().collect()
@@ -197,6 +469,164 @@ impl AppLauncher {
     }
 }
 
+/// Score `candidate` against `query` as a fuzzy subsequence match: every character of
+/// the (lowercased) query must appear in `candidate`, in order, though not necessarily
+/// contiguously. Returns `None` if query isn't a subsequence of candidate at all.
+/// Among matches, rewards consecutive runs, matches that land on a word boundary
+/// (start of string, or right after a space/`-`/`_`, or a capital letter), and matches
+/// near the start of the candidate; penalizes gaps between matched characters and
+/// leftover unmatched length, so "vsc" ranks "Visual Studio Code" above a long name
+/// that merely happens to contain v/s/c somewhere.
+fn score_subsequence(query: &str, candidate: &str) -> Option<i32> {
+    let query_lower: Vec<char> = query.to_lowercase().chars().collect();
+    if query_lower.is_empty() {
+        return Some(0);
+    }
+
+    let candidate_chars: Vec<char> = candidate.chars().collect();
+    let mut qi = 0;
+    let mut score: i32 = 0;
+    let mut last_match: Option<usize> = None;
+
+    for (ci, &c) in candidate_chars.iter().enumerate() {
+        if qi >= query_lower.len() {
+            break;
+        }
+        if c.to_ascii_lowercase() != query_lower[qi] {
+            continue;
+        }
+
+        let at_boundary = ci == 0
+            || matches!(candidate_chars[ci - 1], ' ' | '-' | '_')
+            || c.is_uppercase();
+        if at_boundary {
+            score += 10;
+        }
+
+        match last_match {
+            Some(last) if ci == last + 1 => score += 5,
+            Some(last) => score -= (ci - last - 1) as i32,
+            None => {}
+        }
+
+        score += 20 - (ci as i32).min(20);
+        last_match = Some(ci);
+        qi += 1;
+    }
+
+    if qi < query_lower.len() {
+        return None;
+    }
+
+    let leftover = candidate_chars.len().saturating_sub(last_match.map_or(0, |i| i + 1));
+    score -= leftover as i32 / 2;
+
+    Some(score)
+}
+
+/// Rebuild a `:`-separated pathlist by dropping any entry not present in the clean
+/// `snapshot_at_startup` list (i.e. anything the runtime added after startup), then
+/// de-duplicating while keeping each surviving entry at its lowest-priority (last)
+/// position, since that's the occurrence closest to how the startup session had it.
+#[cfg(not(windows))]
+fn normalize_pathlist(current: &str, snapshot_at_startup: &str) -> String {
+    let snapshot_entries: std::collections::HashSet<&str> =
+        snapshot_at_startup.split(':').filter(|e| !e.is_empty()).collect();
+
+    let filtered: Vec<&str> = current
+        .split(':')
+        .filter(|e| !e.is_empty() && snapshot_entries.contains(e))
+        .collect();
+
+    let mut last_index = std::collections::HashMap::new();
+    for (i, entry) in filtered.iter().enumerate() {
+        last_index.insert(*entry, i);
+    }
+
+    let mut seen = std::collections::HashSet::new();
+    filtered
+        .iter()
+        .enumerate()
+        .filter(|(i, entry)| last_index[*entry] == *i && seen.insert(**entry))
+        .map(|(_, entry)| *entry)
+        .collect::<Vec<_>>()
+        .join(":")
+}
+
+/// Fallback terminal emulator for `Terminal=true` desktop entries. Most distros ship an
+/// `x-terminal-emulator` alternative (Debian/Ubuntu) or honor it as a generic symlink name;
+/// this is the same "just try the conventional name" approach `scan_unix_apps` already takes.
+#[cfg(not(windows))]
+fn terminal_emulator() -> &'static str {
+    "x-terminal-emulator"
+}
+
+/// Parse a `.desktop` file's `[Desktop Entry]` group into an `AppInfo`, or `None` if it's
+/// not displayable (`NoDisplay=true`/`Hidden=true`) or missing a usable `Exec`/`Name`.
+#[cfg(not(windows))]
+fn parse_desktop_entry(contents: &str) -> Option<AppInfo> {
+    let mut in_desktop_entry = false;
+    let mut name = None;
+    let mut exec = None;
+    let mut icon = None;
+    let mut terminal = false;
+    let mut no_display = false;
+    let mut hidden = false;
+
+    for line in contents.lines() {
+        let line = line.trim();
+        if line.starts_with('[') {
+            in_desktop_entry = line == "[Desktop Entry]";
+            continue;
+        }
+        if !in_desktop_entry {
+            continue;
+        }
+
+        let Some((key, value)) = line.split_once('=') else {
+            continue;
+        };
+        match key.trim() {
+            "Name" => name = Some(value.trim().to_string()),
+            "Exec" => exec = Some(value.trim().to_string()),
+            "Icon" => icon = Some(value.trim().to_string()),
+            "Terminal" => terminal = value.trim().eq_ignore_ascii_case("true"),
+            "NoDisplay" => no_display = value.trim().eq_ignore_ascii_case("true"),
+            "Hidden" => hidden = value.trim().eq_ignore_ascii_case("true"),
+            _ => {}
+        }
+    }
+
+    if no_display || hidden {
+        return None;
+    }
+
+    let name = name?;
+    let exec = exec?;
+    let mut tokens = strip_field_codes(&exec).into_iter();
+    let binary = tokens.next()?;
+
+    Some(AppInfo {
+        name: name.clone(),
+        path: PathBuf::from(binary),
+        display_name: name,
+        exec_args: tokens.collect(),
+        icon,
+        terminal,
+    })
+}
+
+/// Strip XDG field codes (`%f`, `%F`, `%u`, `%U`, and the rarer `%i`/`%c`/`%k`) from an
+/// `Exec` value's tokens - they're substituted by the launching desktop environment with
+/// files/URIs/icon/etc, which Fluffy never supplies.
+#[cfg(not(windows))]
+fn strip_field_codes(exec: &str) -> Vec<String> {
+    exec.split_whitespace()
+        .filter(|tok| !matches!(*tok, "%f" | "%F" | "%u" | "%U" | "%i" | "%c" | "%k"))
+        .map(|tok| tok.to_string())
+        .collect()
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -213,4 +643,69 @@ mod tests {
         let launcher = AppLauncher::new();
         assert!(launcher.find_app("notepad").is_some());
     }
+
+    #[test]
+    #[cfg(not(windows))]
+    fn test_normalize_pathlist_drops_runtime_added_entries() {
+        let snapshot = "/usr/bin:/usr/local/bin";
+        let current = "/tmp/appimage/usr/bin:/usr/bin:/usr/local/bin";
+        assert_eq!(normalize_pathlist(current, snapshot), "/usr/bin:/usr/local/bin");
+    }
+
+    #[test]
+    #[cfg(not(windows))]
+    fn test_normalize_pathlist_dedupes_keeping_last_occurrence() {
+        let snapshot = "/usr/bin:/usr/local/bin";
+        let current = "/usr/bin:/usr/local/bin:/usr/bin";
+        assert_eq!(normalize_pathlist(current, snapshot), "/usr/local/bin:/usr/bin");
+    }
+
+    #[test]
+    #[cfg(not(windows))]
+    fn test_parse_desktop_entry_strips_field_codes_and_terminal() {
+        let contents = "[Desktop Entry]\nName=Vim\nExec=vim %f\nTerminal=true\nIcon=vim\n";
+        let app = parse_desktop_entry(contents).unwrap();
+        assert_eq!(app.name, "Vim");
+        assert_eq!(app.path, PathBuf::from("vim"));
+        assert!(app.exec_args.is_empty());
+        assert!(app.terminal);
+        assert_eq!(app.icon, Some("vim".to_string()));
+    }
+
+    #[test]
+    #[cfg(not(windows))]
+    fn test_parse_desktop_entry_skips_hidden() {
+        let contents = "[Desktop Entry]\nName=Internal\nExec=internal-tool\nNoDisplay=true\n";
+        assert!(parse_desktop_entry(contents).is_none());
+    }
+
+    #[test]
+    fn test_untracked_pid_reports_not_running() {
+        assert!(!is_tracked(u32::MAX));
+    }
+
+    #[test]
+    #[cfg(not(windows))]
+    fn test_kill_by_name_errors_when_nothing_tracked() {
+        let launcher = AppLauncher::new();
+        assert!(launcher.kill_by_name("definitely-not-a-tracked-app").is_err());
+    }
+
+    #[test]
+    fn test_score_subsequence_matches_abbreviation() {
+        assert!(score_subsequence("vsc", "Visual Studio Code").is_some());
+        assert!(score_subsequence("chrm", "Google Chrome").is_some());
+    }
+
+    #[test]
+    fn test_score_subsequence_rejects_non_subsequence() {
+        assert!(score_subsequence("zzz", "Google Chrome").is_none());
+    }
+
+    #[test]
+    fn test_score_subsequence_prefers_word_boundary_and_consecutive_runs() {
+        let boundary_heavy = score_subsequence("vsc", "Visual Studio Code").unwrap();
+        let scattered = score_subsequence("vsc", "aVbScd").unwrap();
+        assert!(boundary_heavy > scattered);
+    }
 }
@@ -1,8 +1,14 @@
 // Action modules for voice command execution
+pub mod batch;
+pub mod confirm;
 pub mod filesystem;
+pub mod fs_backend;
 pub mod launcher;
 pub mod safety;
 
+pub use batch::{BatchAction, BatchOutcome, BatchResult};
+pub use confirm::{AutoApprove, AutoDeny, CliConfirmer, Confirmer};
 pub use filesystem::{FileSystemAction, ActionType};
+pub use fs_backend::{FileSystem, InMemoryFs, RealFs};
 pub use launcher::AppLauncher;
 pub use safety::{SafetyValidator, SafetyLevel};
@@ -1,5 +1,10 @@
-use std::path::PathBuf;
+use std::fs::File;
+use std::io::{self, Read as _, Write as _};
+use std::path::{Path, PathBuf};
+use fs2::FileExt;
 use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+use super::fs_backend::FileSystem;
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub enum SafetyLevel {
@@ -11,6 +16,21 @@ pub enum SafetyLevel {
 pub struct SafetyValidator {
     protected_paths: Vec<PathBuf>,
     allowed_paths: Vec<PathBuf>,
+    needs_confirmation_paths: Vec<PathBuf>,
+    system_extensions: Vec<String>,
+}
+
+/// On-disk shape for a user-supplied safety config, merged over the OS defaults by
+/// `SafetyValidator::from_config`. Entries support `~` and `$VAR`/`${VAR}` expansion.
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct SafetyConfigFile {
+    #[serde(default)]
+    protected_paths: Vec<String>,
+    #[serde(default)]
+    allowed_paths: Vec<String>,
+    #[serde(default)]
+    needs_confirmation_paths: Vec<String>,
+    #[serde(default)]
     system_extensions: Vec<String>,
 }
 
@@ -27,10 +47,78 @@ impl SafetyValidator {
         Self {
             protected_paths,
             allowed_paths,
+            needs_confirmation_paths: Vec::new(),
             system_extensions,
         }
     }
 
+    /// Build a validator from OS defaults merged with a user config file. Falls back
+    /// cleanly to `Self::new()` if `path` doesn't exist or can't be parsed, so a
+    /// missing or malformed config never blocks startup.
+    pub fn from_config(path: &Path) -> Self {
+        let mut validator = Self::new();
+
+        let config = match Self::read_config(path) {
+            Ok(config) => config,
+            Err(_) => return validator,
+        };
+
+        validator.protected_paths.extend(config.protected_paths.iter().map(|s| expand_path(s)));
+        validator.allowed_paths.extend(config.allowed_paths.iter().map(|s| expand_path(s)));
+        validator.needs_confirmation_paths.extend(config.needs_confirmation_paths.iter().map(|s| expand_path(s)));
+        validator.system_extensions.extend(config.system_extensions);
+
+        validator
+    }
+
+    /// Read and parse `path`, taking a shared lock for the duration of the read so a
+    /// concurrent writer's atomic rename can't be observed mid-write.
+    fn read_config(path: &Path) -> io::Result<SafetyConfigFile> {
+        let mut file = File::open(path)?;
+        file.lock_shared()?;
+        let mut contents = String::new();
+        let result = file.read_to_string(&mut contents);
+        let _ = file.unlock();
+        result?;
+
+        serde_json::from_str(&contents)
+            .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))
+    }
+
+    /// Persist the current protected/allowed/needs-confirmation/extension lists to
+    /// `path` as pretty-printed JSON, via a sibling temp file + rename so readers never
+    /// see a partial write.
+    pub fn save_to_config(&self, path: &Path) -> Result<(), String> {
+        let config = SafetyConfigFile {
+            protected_paths: self.protected_paths.iter().map(|p| p.display().to_string()).collect(),
+            allowed_paths: self.allowed_paths.iter().map(|p| p.display().to_string()).collect(),
+            needs_confirmation_paths: self.needs_confirmation_paths.iter().map(|p| p.display().to_string()).collect(),
+            system_extensions: self.system_extensions.clone(),
+        };
+
+        let json = serde_json::to_string_pretty(&config)
+            .map_err(|e| format!("Failed to serialize safety config: {}", e))?;
+
+        let suffix = Uuid::new_v4().simple().to_string()[..8].to_string();
+        let file_name = path
+            .file_name()
+            .map(|n| n.to_string_lossy().into_owned())
+            .unwrap_or_else(|| "safety_config.json".to_string());
+        let tmp_path = path.with_file_name(format!("{}.{}.tmp", file_name, suffix));
+
+        let mut tmp_file = File::create(&tmp_path)
+            .map_err(|e| format!("Failed to create temp config file: {}", e))?;
+        tmp_file.write_all(json.as_bytes())
+            .map_err(|e| format!("Failed to write temp config file: {}", e))?;
+        tmp_file.sync_all().map_err(|e| format!("Failed to sync temp config file: {}", e))?;
+        drop(tmp_file);
+
+        std::fs::rename(&tmp_path, path).map_err(|e| {
+            let _ = std::fs::remove_file(&tmp_path);
+            format!("Failed to save safety config: {}", e)
+        })
+    }
+
     /// Get protected paths based on OS
     fn get_protected_paths() -> Vec<PathBuf> {
         if cfg!(windows) {
@@ -81,23 +169,14 @@ impl SafetyValidator {
         }
     }
 
-    /// Check if a path is safe to operate on
+    /// Check if a path is safe to operate on. The path is fully contained before
+    /// classification: symlinks and `..` components in an already-existing prefix are
+    /// resolved by `canonicalize`, and a `..` in a not-yet-existing suffix (which
+    /// canonicalize can't see) is rejected outright rather than trusted.
     pub fn check_path(&self, path: &PathBuf) -> SafetyLevel {
-        // Normalize path
-        let canonical = match path.canonicalize() {
-            Ok(p) => p,
-            Err(_) => {
-                // Path doesn't exist yet (e.g., creating new file)
-                // Check parent directory
-                if let Some(parent) = path.parent() {
-                    match parent.canonicalize() {
-                        Ok(p) => p,
-                        Err(_) => return SafetyLevel::Blocked,
-                    }
-                } else {
-                    return SafetyLevel::Blocked;
-                }
-            }
+        let canonical = match super::fs_backend::resolve_containment(path) {
+            Some(p) => p,
+            None => return SafetyLevel::Blocked,
         };
 
         // Check if in protected paths
@@ -105,6 +184,11 @@ impl SafetyValidator {
             return SafetyLevel::Blocked;
         }
 
+        // User-configured paths that always need confirmation, even if also allowed
+        if self.is_needs_confirmation(&canonical) {
+            return SafetyLevel::NeedsConfirmation;
+        }
+
         // Check if in allowed paths (safe)
         if self.is_allowed(&canonical) {
             // Check for system file extensions
@@ -138,6 +222,11 @@ impl SafetyValidator {
         false
     }
 
+    /// Check if path falls under a user-configured "always confirm" rule
+    fn is_needs_confirmation(&self, path: &Path) -> bool {
+        self.needs_confirmation_paths.iter().any(|p| path.starts_with(p))
+    }
+
     /// Check if file is a system file
     fn is_system_file(&self, path: &PathBuf) -> bool {
         if let Some(ext) = path.extension() {
@@ -152,8 +241,88 @@ impl SafetyValidator {
     pub fn is_system_critical(&self, path: &PathBuf) -> bool {
         matches!(self.check_path(path), SafetyLevel::Blocked)
     }
+
+    /// Same classification as `check_path`, but containment resolution goes through
+    /// `fs` instead of unconditionally hitting the real disk. `RealFs::resolve_for_safety`
+    /// always canonicalizes (so a symlink pointing outside an allowed directory is still
+    /// caught, whether or not the path already exists); backends that don't model a real,
+    /// symlink-capable filesystem (the in-memory fake) just trust the path as given.
+    pub fn check_path_in<F: FileSystem>(&self, path: &Path, fs: &F) -> SafetyLevel {
+        let canonical = fs.resolve_for_safety(path);
+
+        if self.is_protected(&canonical) {
+            return SafetyLevel::Blocked;
+        }
+
+        if self.is_needs_confirmation(&canonical) {
+            return SafetyLevel::NeedsConfirmation;
+        }
+
+        if self.is_allowed(&canonical) {
+            if self.is_system_file(&canonical) {
+                return SafetyLevel::NeedsConfirmation;
+            }
+            return SafetyLevel::Safe;
+        }
+
+        SafetyLevel::NeedsConfirmation
+    }
+}
+
+/// Expand a leading `~` to the home directory and `$VAR`/`${VAR}` references to
+/// environment variables. Unresolvable variables are left as-is.
+pub(crate) fn expand_path(raw: &str) -> PathBuf {
+    let with_home = if let Some(rest) = raw.strip_prefix('~') {
+        let home = dirs::home_dir().unwrap_or_else(|| PathBuf::from("."));
+        format!("{}{}", home.display(), rest)
+    } else {
+        raw.to_string()
+    };
+
+    let mut expanded = String::with_capacity(with_home.len());
+    let mut chars = with_home.chars().peekable();
+    while let Some(c) = chars.next() {
+        if c != '$' {
+            expanded.push(c);
+            continue;
+        }
+
+        let braced = chars.peek() == Some(&'{');
+        let var_name: String = if braced {
+            chars.next();
+            let name: String = chars.by_ref().take_while(|&c| c != '}').collect();
+            name
+        } else {
+            let mut name = String::new();
+            while let Some(&next) = chars.peek() {
+                if next.is_alphanumeric() || next == '_' {
+                    name.push(next);
+                    chars.next();
+                } else {
+                    break;
+                }
+            }
+            name
+        };
+
+        match std::env::var(&var_name) {
+            Ok(value) => expanded.push_str(&value),
+            Err(_) if braced => {
+                expanded.push_str("${");
+                expanded.push_str(&var_name);
+                expanded.push('}');
+            }
+            Err(_) => {
+                expanded.push('$');
+                expanded.push_str(&var_name);
+            }
+        }
+    }
+
+    PathBuf::from(expanded)
 }
 
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -178,4 +347,53 @@ mod tests {
         let result = validator.check_path(&doc_path);
         assert!(!matches!(result, SafetyLevel::Blocked));
     }
+
+    #[test]
+    fn test_from_config_merges_extra_needs_confirmation_path() {
+        let temp_dir = std::env::temp_dir();
+        let config_path = temp_dir.join("fluffy_safety_config_test.json");
+        std::fs::write(
+            &config_path,
+            r#"{"needs_confirmation_paths": ["$HOME/scratch"], "protected_paths": [], "allowed_paths": [], "system_extensions": []}"#
+                .replace("$HOME", &dirs::home_dir().unwrap().display().to_string()),
+        ).unwrap();
+
+        let validator = SafetyValidator::from_config(&config_path);
+        let scratch_file = dirs::home_dir().unwrap().join("scratch").join("test.txt");
+        assert!(matches!(
+            validator.check_path_in(&scratch_file, &super::super::fs_backend::RealFs),
+            SafetyLevel::NeedsConfirmation
+        ));
+
+        let _ = std::fs::remove_file(&config_path);
+    }
+
+    #[test]
+    fn test_from_config_falls_back_to_defaults_when_missing() {
+        let missing = PathBuf::from("/nonexistent/fluffy_safety_config_that_does_not_exist.json");
+        let validator = SafetyValidator::from_config(&missing);
+        let home = dirs::home_dir().unwrap();
+        let doc_path = home.join("Documents").join("test.txt");
+        assert!(!matches!(validator.check_path(&doc_path), SafetyLevel::Blocked));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_check_path_in_follows_symlink_out_of_allowed_dir() {
+        let home = dirs::home_dir().unwrap();
+        let allowed_dir = home.join("Documents");
+        std::fs::create_dir_all(&allowed_dir).unwrap();
+
+        let link_path = allowed_dir.join("fluffy_safety_symlink_escape_test");
+        let _ = std::fs::remove_file(&link_path);
+        std::os::unix::fs::symlink("/etc", &link_path).unwrap();
+
+        let validator = SafetyValidator::new();
+        let result = validator.check_path_in(&link_path, &super::super::fs_backend::RealFs);
+        let _ = std::fs::remove_file(&link_path);
+
+        // The symlink lives under an allowed dir, but its target (/etc) is protected,
+        // so resolving through the symlink must win over trusting the unresolved path.
+        assert!(matches!(result, SafetyLevel::Blocked));
+    }
 }
@@ -0,0 +1,180 @@
+use once_cell::sync::Lazy;
+use serde::Serialize;
+use std::collections::HashMap;
+use std::net::IpAddr;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Mutex;
+
+/// Toggle for reverse-DNS resolution of remote hosts, mirroring `IS_UI_ACTIVE`.
+/// Off by default so a fresh install doesn't start issuing PTR lookups unasked.
+pub static DNS_RESOLVE_ENABLED: AtomicBool = AtomicBool::new(false);
+
+/// Cache of remote IP -> resolved hostname. Lookups are issued on a background thread;
+/// `collect_connections` serves whatever is cached immediately so the 2-second broadcast
+/// loop never blocks on a PTR query.
+static IP_TABLE: Lazy<Mutex<HashMap<IpAddr, String>>> = Lazy::new(|| Mutex::new(HashMap::new()));
+
+/// IPs currently being resolved, so repeated ticks don't spawn duplicate lookups for
+/// the same address.
+static IN_FLIGHT: Lazy<Mutex<std::collections::HashSet<IpAddr>>> =
+    Lazy::new(|| Mutex::new(std::collections::HashSet::new()));
+
+#[derive(Serialize, Clone)]
+pub struct ConnectionInfo {
+    pub local_addr: String,
+    pub local_port: u16,
+    pub remote_addr: String,
+    pub remote_host: Option<String>,
+    pub remote_port: u16,
+    pub protocol: String, // "tcp" | "udp"
+    pub state: String,
+    pub pid: Option<u32>,
+}
+
+/// Enumerate active TCP/UDP connections for the current refresh cycle, attaching the
+/// cached remote hostname when DNS resolution is enabled. An address whose lookup is
+/// still in flight (or failed) gets `remote_host: None`.
+pub fn collect_connections() -> Vec<ConnectionInfo> {
+    let raw = enumerate_raw_connections();
+
+    if DNS_RESOLVE_ENABLED.load(Ordering::SeqCst) {
+        for conn in &raw {
+            if let Ok(ip) = conn.remote_addr.parse::<IpAddr>() {
+                queue_resolve(ip);
+            }
+        }
+    }
+
+    let cache = IP_TABLE.lock().unwrap();
+    raw.into_iter()
+        .map(|mut conn| {
+            if DNS_RESOLVE_ENABLED.load(Ordering::SeqCst) {
+                if let Ok(ip) = conn.remote_addr.parse::<IpAddr>() {
+                    conn.remote_host = cache.get(&ip).cloned();
+                }
+            }
+            conn
+        })
+        .collect()
+}
+
+fn queue_resolve(ip: IpAddr) {
+    {
+        let cache = IP_TABLE.lock().unwrap();
+        if cache.contains_key(&ip) {
+            return;
+        }
+    }
+
+    let mut in_flight = IN_FLIGHT.lock().unwrap();
+    if !in_flight.insert(ip) {
+        return; // already being resolved
+    }
+    drop(in_flight);
+
+    std::thread::spawn(move || {
+        if let Some(host) = reverse_dns_lookup(ip) {
+            IP_TABLE.lock().unwrap().insert(ip, host);
+        }
+        IN_FLIGHT.lock().unwrap().remove(&ip);
+    });
+}
+
+fn reverse_dns_lookup(ip: IpAddr) -> Option<String> {
+    // dns_lookup::lookup_addr issues a blocking PTR query; safe here since this only
+    // ever runs on the background resolver thread, never on the broadcast loop.
+    dns_lookup::lookup_addr(&ip).ok()
+}
+
+#[cfg(target_os = "windows")]
+fn enumerate_raw_connections() -> Vec<ConnectionInfo> {
+    use std::net::Ipv4Addr;
+    use windows_sys::Win32::Foundation::NO_ERROR;
+    use windows_sys::Win32::NetworkManagement::IpHelper::{
+        GetExtendedTcpTable, GetExtendedUdpTable, MIB_TCPROW_OWNER_PID, MIB_TCPTABLE_OWNER_PID,
+        MIB_UDPROW_OWNER_PID, MIB_UDPTABLE_OWNER_PID, TCP_TABLE_OWNER_PID_ALL,
+        UDP_TABLE_OWNER_PID,
+    };
+    use windows_sys::Win32::Networking::WinSock::AF_INET;
+
+    let mut out = Vec::new();
+
+    unsafe {
+        let mut size: u32 = 0;
+        GetExtendedTcpTable(std::ptr::null_mut(), &mut size, 0, AF_INET as u32, TCP_TABLE_OWNER_PID_ALL, 0);
+        let mut buf = vec![0u8; size as usize];
+        if GetExtendedTcpTable(buf.as_mut_ptr() as *mut _, &mut size, 0, AF_INET as u32, TCP_TABLE_OWNER_PID_ALL, 0) == NO_ERROR {
+            let tcp_table = &*(buf.as_ptr() as *const MIB_TCPTABLE_OWNER_PID);
+            let rows = std::slice::from_raw_parts(
+                tcp_table.table.as_ptr() as *const MIB_TCPROW_OWNER_PID,
+                tcp_table.dwNumEntries as usize,
+            );
+            for row in rows {
+                out.push(ConnectionInfo {
+                    local_addr: Ipv4Addr::from(u32::from_be(row.dwLocalAddr)).to_string(),
+                    local_port: u16::from_be((row.dwLocalPort & 0xFFFF) as u16),
+                    remote_addr: Ipv4Addr::from(u32::from_be(row.dwRemoteAddr)).to_string(),
+                    remote_host: None,
+                    remote_port: u16::from_be((row.dwRemotePort & 0xFFFF) as u16),
+                    protocol: "tcp".to_string(),
+                    state: tcp_state_name(row.dwState),
+                    pid: Some(row.dwOwningPid),
+                });
+            }
+        }
+    }
+
+    unsafe {
+        let mut size: u32 = 0;
+        GetExtendedUdpTable(std::ptr::null_mut(), &mut size, 0, AF_INET as u32, UDP_TABLE_OWNER_PID, 0);
+        let mut buf = vec![0u8; size as usize];
+        if GetExtendedUdpTable(buf.as_mut_ptr() as *mut _, &mut size, 0, AF_INET as u32, UDP_TABLE_OWNER_PID, 0) == NO_ERROR {
+            let udp_table = &*(buf.as_ptr() as *const MIB_UDPTABLE_OWNER_PID);
+            let rows = std::slice::from_raw_parts(
+                udp_table.table.as_ptr() as *const MIB_UDPROW_OWNER_PID,
+                udp_table.dwNumEntries as usize,
+            );
+            for row in rows {
+                out.push(ConnectionInfo {
+                    local_addr: Ipv4Addr::from(u32::from_be(row.dwLocalAddr)).to_string(),
+                    local_port: u16::from_be((row.dwLocalPort & 0xFFFF) as u16),
+                    remote_addr: "0.0.0.0".to_string(),
+                    remote_host: None,
+                    remote_port: 0,
+                    protocol: "udp".to_string(),
+                    state: "stateless".to_string(),
+                    pid: Some(row.dwOwningPid),
+                });
+            }
+        }
+    }
+
+    out
+}
+
+#[cfg(target_os = "windows")]
+fn tcp_state_name(state: u32) -> String {
+    // MIB_TCP_STATE_* values from the IP Helper API.
+    match state {
+        1 => "closed",
+        2 => "listening",
+        3 => "syn_sent",
+        4 => "syn_received",
+        5 => "established",
+        6 => "fin_wait1",
+        7 => "fin_wait2",
+        8 => "close_wait",
+        9 => "closing",
+        10 => "last_ack",
+        11 => "time_wait",
+        12 => "delete_tcb",
+        _ => "unknown",
+    }
+    .to_string()
+}
+
+#[cfg(not(target_os = "windows"))]
+fn enumerate_raw_connections() -> Vec<ConnectionInfo> {
+    // No privileged connection table on this platform stub.
+    Vec::new()
+}
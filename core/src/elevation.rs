@@ -0,0 +1,243 @@
+//! UAC self-elevation for commands that need administrator rights. When a command's
+//! `PermissionDecision` is `RequireElevation`, `run_elevated` checks whether this
+//! process already holds an elevated token; if not, it relaunches itself with the
+//! `ShellExecuteEx` "runas" verb, passing the serialized command as a command-line
+//! argument, and waits for the elevated instance to report back its result over a
+//! one-shot named pipe.
+
+use crate::ipc::command::Command;
+
+/// Subcommand this binary recognizes when launched by `relaunch_elevated` to act as
+/// the elevated helper: `fluffy-core.exe --elevated-exec <pipe-name> <command-json>`.
+pub const ELEVATED_EXEC_FLAG: &str = "--elevated-exec";
+
+#[cfg(target_os = "windows")]
+mod windows {
+    use super::Command;
+    use std::ffi::OsStr;
+    use std::io::{Read, Write};
+    use std::os::windows::ffi::OsStrExt;
+    use std::ptr;
+    use windows_sys::Win32::Foundation::{CloseHandle, GetLastError, HANDLE, INVALID_HANDLE_VALUE};
+    use windows_sys::Win32::Security::{GetTokenInformation, TokenElevation, TOKEN_ELEVATION, TOKEN_QUERY};
+    use windows_sys::Win32::System::Pipes::{ConnectNamedPipe, CreateNamedPipeW, PIPE_ACCESS_DUPLEX, PIPE_TYPE_BYTE, PIPE_WAIT};
+    use windows_sys::Win32::System::Threading::{GetCurrentProcess, OpenProcessToken};
+    use windows_sys::Win32::UI::Shell::{ShellExecuteExW, SEE_MASK_NOCLOSEPROCESS, SHELLEXECUTEINFOW};
+    use windows_sys::Win32::UI::WindowsAndMessaging::SW_HIDE;
+
+    fn encode_wide(s: &str) -> Vec<u16> {
+        OsStr::new(s).encode_wide().chain(std::iter::once(0)).collect()
+    }
+
+    /// Quote `arg` for the Windows command line the way `CommandLineToArgvW` expects to
+    /// unquote it: naive `"` -> `\"` replacement (the previous approach) ignores
+    /// backslash-parity rules, so a JSON string ending in `\` (or containing `\"`)
+    /// would shift quoting for the rest of the command line. This implements the
+    /// documented algorithm: a run of backslashes is only escaped (doubled) when it's
+    /// immediately followed by a quote (or the closing quote we add at the end).
+    fn quote_arg(arg: &str) -> String {
+        let mut out = String::with_capacity(arg.len() + 2);
+        out.push('"');
+
+        let mut chars = arg.chars().peekable();
+        loop {
+            let mut backslashes = 0;
+            while chars.peek() == Some(&'\\') {
+                backslashes += 1;
+                chars.next();
+            }
+
+            match chars.peek() {
+                None => {
+                    // Trailing backslashes are followed by the closing quote we're
+                    // about to add, so each one must be doubled.
+                    out.extend(std::iter::repeat('\\').take(backslashes * 2));
+                    break;
+                }
+                Some('"') => {
+                    out.extend(std::iter::repeat('\\').take(backslashes * 2 + 1));
+                    out.push('"');
+                    chars.next();
+                }
+                Some(_) => {
+                    out.extend(std::iter::repeat('\\').take(backslashes));
+                    out.push(chars.next().unwrap());
+                }
+            }
+        }
+
+        out.push('"');
+        out
+    }
+
+    pub fn is_elevated() -> bool {
+        unsafe {
+            let mut token: HANDLE = 0;
+            if OpenProcessToken(GetCurrentProcess(), TOKEN_QUERY, &mut token) == 0 {
+                return false;
+            }
+
+            let mut elevation = TOKEN_ELEVATION { TokenIsElevated: 0 };
+            let mut returned_len = 0u32;
+            let ok = GetTokenInformation(
+                token,
+                TokenElevation,
+                &mut elevation as *mut _ as *mut _,
+                std::mem::size_of::<TOKEN_ELEVATION>() as u32,
+                &mut returned_len,
+            );
+            CloseHandle(token);
+
+            ok != 0 && elevation.TokenIsElevated != 0
+        }
+    }
+
+    /// Relaunch this executable elevated with `--elevated-exec <pipe> <command-json>`,
+    /// and block until the elevated instance writes its result to the pipe.
+    pub fn run_elevated(cmd: &Command) -> Result<String, String> {
+        let pipe_name = format!(r"\\.\pipe\fluffy-elevate-{}", uuid::Uuid::new_v4().simple());
+        let pipe_name_wide = encode_wide(&pipe_name);
+
+        let pipe_handle = unsafe {
+            CreateNamedPipeW(
+                pipe_name_wide.as_ptr(),
+                PIPE_ACCESS_DUPLEX,
+                PIPE_TYPE_BYTE | PIPE_WAIT,
+                1,
+                4096,
+                4096,
+                0,
+                ptr::null(),
+            )
+        };
+        if pipe_handle == INVALID_HANDLE_VALUE {
+            return Err(format!("Failed to create elevation pipe: error {}", unsafe { GetLastError() }));
+        }
+
+        let exe_path = std::env::current_exe().map_err(|e| e.to_string())?;
+        let cmd_json = serde_json::to_string(cmd).map_err(|e| e.to_string())?;
+        let params = format!(
+            "{} {} {}",
+            super::ELEVATED_EXEC_FLAG,
+            quote_arg(&pipe_name),
+            quote_arg(&cmd_json)
+        );
+
+        let verb = encode_wide("runas");
+        let file = encode_wide(&exe_path.to_string_lossy());
+        let params_wide = encode_wide(&params);
+
+        let mut info = SHELLEXECUTEINFOW {
+            cbSize: std::mem::size_of::<SHELLEXECUTEINFOW>() as u32,
+            fMask: SEE_MASK_NOCLOSEPROCESS,
+            hwnd: 0,
+            lpVerb: verb.as_ptr(),
+            lpFile: file.as_ptr(),
+            lpParameters: params_wide.as_ptr(),
+            lpDirectory: ptr::null(),
+            nShow: SW_HIDE,
+            hInstApp: 0,
+            lpIDList: ptr::null_mut(),
+            lpClass: ptr::null(),
+            hkeyClass: 0,
+            dwHotKey: 0,
+            Anonymous: Default::default(),
+            hProcess: 0,
+        };
+
+        let launched = unsafe { ShellExecuteExW(&mut info) };
+        if launched == 0 {
+            unsafe { CloseHandle(pipe_handle) };
+            return Err(format!("User declined or failed to launch elevated process: error {}", unsafe { GetLastError() }));
+        }
+        if info.hProcess != 0 {
+            unsafe { CloseHandle(info.hProcess) };
+        }
+
+        if unsafe { ConnectNamedPipe(pipe_handle, ptr::null_mut()) } == 0 {
+            let err = unsafe { GetLastError() };
+            const ERROR_PIPE_CONNECTED: u32 = 535;
+            if err != ERROR_PIPE_CONNECTED {
+                unsafe { CloseHandle(pipe_handle) };
+                return Err(format!("Failed to connect to elevated process: error {}", err));
+            }
+        }
+
+        let mut file = unsafe { windows_pipe_to_file(pipe_handle) };
+        let mut response = String::new();
+        let read_result = file.read_to_string(&mut response);
+        unsafe { CloseHandle(pipe_handle) };
+        read_result.map_err(|e| format!("Failed to read elevation response: {}", e))?;
+
+        Ok(response)
+    }
+
+    /// Run as the elevated helper process: execute `cmd` and write the result back to
+    /// the waiting pipe server before exiting.
+    pub fn run_as_helper(pipe_name: &str, cmd: Command, execute: impl FnOnce(Command) -> String) {
+        let result = execute(cmd);
+
+        let pipe_name_wide = encode_wide(pipe_name);
+        let handle = unsafe {
+            windows_sys::Win32::Storage::FileSystem::CreateFileW(
+                pipe_name_wide.as_ptr(),
+                windows_sys::Win32::Foundation::GENERIC_WRITE,
+                0,
+                ptr::null(),
+                windows_sys::Win32::Storage::FileSystem::OPEN_EXISTING,
+                0,
+                0,
+            )
+        };
+        if handle == INVALID_HANDLE_VALUE {
+            eprintln!("[Fluffy Core] Elevated helper couldn't open result pipe: error {}", unsafe { GetLastError() });
+            return;
+        }
+
+        let mut file = unsafe { windows_pipe_to_file(handle) };
+        let _ = file.write_all(result.as_bytes());
+        unsafe { CloseHandle(handle) };
+    }
+
+    /// Wrap a raw Windows HANDLE as a `std::fs::File` so pipe I/O can use `Read`/`Write`.
+    unsafe fn windows_pipe_to_file(handle: HANDLE) -> std::fs::File {
+        use std::os::windows::io::{FromRawHandle, RawHandle};
+        std::fs::File::from_raw_handle(handle as RawHandle)
+    }
+}
+
+#[cfg(not(target_os = "windows"))]
+mod other {
+    use super::Command;
+
+    /// No UAC concept outside Windows; treat root as "elevated" and anything else as
+    /// not, matching how `sudo`-gated scripts usually check privilege on unix.
+    pub fn is_elevated() -> bool {
+        unsafe { libc_geteuid() == 0 }
+    }
+
+    #[cfg(unix)]
+    unsafe fn libc_geteuid() -> u32 {
+        extern "C" {
+            fn geteuid() -> u32;
+        }
+        geteuid()
+    }
+
+    #[cfg(not(unix))]
+    unsafe fn libc_geteuid() -> u32 {
+        0
+    }
+
+    pub fn run_elevated(_cmd: &Command) -> Result<String, String> {
+        Err("Self-elevation is only implemented on Windows".to_string())
+    }
+
+    pub fn run_as_helper(_pipe_name: &str, _cmd: Command, _execute: impl FnOnce(Command) -> String) {}
+}
+
+#[cfg(target_os = "windows")]
+pub use windows::{is_elevated, run_as_helper, run_elevated};
+
+#[cfg(not(target_os = "windows"))]
+pub use other::{is_elevated, run_as_helper, run_elevated};
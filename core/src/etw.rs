@@ -1,15 +1,556 @@
 use dashmap::DashMap;
 use once_cell::sync::Lazy;
+use std::collections::HashMap;
+use std::net::IpAddr;
+use std::sync::Mutex;
+use std::time::Duration;
 
+/// Per-PID (tx, rx) byte deltas since the last `collect_processes` read.
+/// `collect_processes` is the single consumer: it reads and zeroes each entry every tick.
 pub static NETWORK_DELTAS: Lazy<DashMap<u32, (u64, u64)>> = Lazy::new(DashMap::new);
 
+#[derive(Clone, Copy, PartialEq, Eq, Hash)]
+enum Proto {
+    Tcp,
+    Udp,
+}
+
+/// Identity of a local socket endpoint, used to resolve a captured packet to a PID.
+#[derive(Clone, Copy, PartialEq, Eq, Hash)]
+struct SocketKey {
+    proto: Proto,
+    ip: IpAddr,
+    port: u16,
+}
+
+#[derive(Default, Clone, Copy)]
+struct Utilization {
+    tx: u64,
+    rx: u64,
+}
+
+/// local socket -> owning PID, refreshed on a short interval so short-lived
+/// connections (e.g. DNS, HTTP requests) are still resolvable when their packets arrive.
+static SOCKET_TABLE: Lazy<Mutex<HashMap<SocketKey, u32>>> = Lazy::new(|| Mutex::new(HashMap::new()));
+
+/// Running totals per connection since the last fold, keyed by the same socket identity
+/// the packet was captured against (i.e. *our* side of the connection).
+static CONNECTION_TOTALS: Lazy<Mutex<HashMap<SocketKey, Utilization>>> =
+    Lazy::new(|| Mutex::new(HashMap::new()));
+
+const SOCKET_TABLE_REFRESH: Duration = Duration::from_millis(500);
+const FOLD_INTERVAL: Duration = Duration::from_secs(2);
+
 pub struct NetworkMonitor;
 
 impl NetworkMonitor {
+    /// Spin up the capture pipeline: a socket-table refresher plus a platform-specific
+    /// packet observer, and a folding thread that rolls connection totals up to per-PID
+    /// deltas in `NETWORK_DELTAS` every `FOLD_INTERVAL`.
     pub fn start() {
         #[cfg(target_os = "windows")]
-        println!("[Fluffy Core] Network Monitor (Stub) initiated. Network stats will be zeroed.");
+        println!("[Fluffy Core] Network Monitor: starting socket table refresh and ETW kernel-network capture.");
+        #[cfg(not(target_os = "windows"))]
+        println!("[Fluffy Core] Network Monitor: ETW unavailable on this platform, falling back to pnet datalink capture.");
+
+        std::thread::spawn(refresh_socket_table_loop);
+        std::thread::spawn(fold_loop);
+
+        #[cfg(target_os = "windows")]
+        std::thread::spawn(capture_loop_etw);
         #[cfg(not(target_os = "windows"))]
-        println!("[Fluffy Core] Network Monitor (Linux stub) - ETW not available on this platform.");
+        std::thread::spawn(capture_loop_pnet);
+    }
+}
+
+fn refresh_socket_table_loop() {
+    loop {
+        let table = enumerate_socket_table();
+        *SOCKET_TABLE.lock().unwrap() = table;
+        std::thread::sleep(SOCKET_TABLE_REFRESH);
+    }
+}
+
+fn fold_loop() {
+    loop {
+        std::thread::sleep(FOLD_INTERVAL);
+        fold_connections_to_pids();
+    }
+}
+
+/// Roll up per-connection byte totals into per-PID tx/rx and publish them to `NETWORK_DELTAS`.
+/// Connections whose owning PID can no longer be resolved (socket already closed) are dropped
+/// rather than misattributed to a stale PID.
+fn fold_connections_to_pids() {
+    let mut totals = CONNECTION_TOTALS.lock().unwrap();
+    if totals.is_empty() {
+        return;
+    }
+    let table = SOCKET_TABLE.lock().unwrap();
+
+    let mut per_pid: HashMap<u32, (u64, u64)> = HashMap::new();
+    for (key, util) in totals.drain() {
+        if let Some(&pid) = table.get(&key) {
+            let entry = per_pid.entry(pid).or_insert((0, 0));
+            entry.0 += util.tx;
+            entry.1 += util.rx;
+        }
+        // Unmatched: the owning socket vanished between capture and fold. Drop it.
+    }
+
+    for (pid, (tx, rx)) in per_pid {
+        let mut entry = NETWORK_DELTAS.entry(pid).or_insert((0, 0));
+        entry.0 += tx;
+        entry.1 += rx;
+    }
+}
+
+fn record_packet(key: SocketKey, is_send: bool, bytes: u64) {
+    let mut totals = CONNECTION_TOTALS.lock().unwrap();
+    let util = totals.entry(key).or_insert_with(Utilization::default);
+    if is_send {
+        util.tx += bytes;
+    } else {
+        util.rx += bytes;
+    }
+}
+
+#[cfg(target_os = "windows")]
+fn enumerate_socket_table() -> HashMap<SocketKey, u32> {
+    use std::net::Ipv4Addr;
+    use windows_sys::Win32::Foundation::NO_ERROR;
+    use windows_sys::Win32::NetworkManagement::IpHelper::{
+        GetExtendedTcpTable, GetExtendedUdpTable, MIB_TCPROW_OWNER_PID, MIB_TCPTABLE_OWNER_PID,
+        MIB_UDPROW_OWNER_PID, MIB_UDPTABLE_OWNER_PID, TCP_TABLE_OWNER_PID_ALL,
+        UDP_TABLE_OWNER_PID,
+    };
+    use windows_sys::Win32::Networking::WinSock::AF_INET;
+
+    let mut table = HashMap::new();
+
+    // TCP
+    unsafe {
+        let mut size: u32 = 0;
+        GetExtendedTcpTable(
+            std::ptr::null_mut(),
+            &mut size,
+            0,
+            AF_INET as u32,
+            TCP_TABLE_OWNER_PID_ALL,
+            0,
+        );
+        let mut buf = vec![0u8; size as usize];
+        if GetExtendedTcpTable(
+            buf.as_mut_ptr() as *mut _,
+            &mut size,
+            0,
+            AF_INET as u32,
+            TCP_TABLE_OWNER_PID_ALL,
+            0,
+        ) == NO_ERROR
+        {
+            let tcp_table = &*(buf.as_ptr() as *const MIB_TCPTABLE_OWNER_PID);
+            let rows = std::slice::from_raw_parts(
+                tcp_table.table.as_ptr() as *const MIB_TCPROW_OWNER_PID,
+                tcp_table.dwNumEntries as usize,
+            );
+            for row in rows {
+                let ip = Ipv4Addr::from(u32::from_be(row.dwLocalAddr));
+                let port = u16::from_be((row.dwLocalPort & 0xFFFF) as u16);
+                table.insert(
+                    SocketKey {
+                        proto: Proto::Tcp,
+                        ip: IpAddr::V4(ip),
+                        port,
+                    },
+                    row.dwOwningPid,
+                );
+            }
+        }
+    }
+
+    // UDP
+    unsafe {
+        let mut size: u32 = 0;
+        GetExtendedUdpTable(
+            std::ptr::null_mut(),
+            &mut size,
+            0,
+            AF_INET as u32,
+            UDP_TABLE_OWNER_PID,
+            0,
+        );
+        let mut buf = vec![0u8; size as usize];
+        if GetExtendedUdpTable(
+            buf.as_mut_ptr() as *mut _,
+            &mut size,
+            0,
+            AF_INET as u32,
+            UDP_TABLE_OWNER_PID,
+            0,
+        ) == NO_ERROR
+        {
+            let udp_table = &*(buf.as_ptr() as *const MIB_UDPTABLE_OWNER_PID);
+            let rows = std::slice::from_raw_parts(
+                udp_table.table.as_ptr() as *const MIB_UDPROW_OWNER_PID,
+                udp_table.dwNumEntries as usize,
+            );
+            for row in rows {
+                let ip = Ipv4Addr::from(u32::from_be(row.dwLocalAddr));
+                let port = u16::from_be((row.dwLocalPort & 0xFFFF) as u16);
+                table.insert(
+                    SocketKey {
+                        proto: Proto::Udp,
+                        ip: IpAddr::V4(ip),
+                        port,
+                    },
+                    row.dwOwningPid,
+                );
+            }
+        }
+    }
+
+    table
+}
+
+#[cfg(not(target_os = "windows"))]
+fn enumerate_socket_table() -> HashMap<SocketKey, u32> {
+    // No privileged owner-pid table on this platform stub; the pnet fallback below
+    // attributes unmatched traffic to nothing rather than guessing.
+    HashMap::new()
+}
+
+/// Run an ETW kernel-network provider session, resolving each captured packet's local
+/// socket against `SOCKET_TABLE` and accumulating byte counts into `CONNECTION_TOTALS`.
+/// Blocks in `ProcessTrace` for the lifetime of the program, same as `capture_loop_pnet`
+/// blocks in its datalink receive loop.
+#[cfg(target_os = "windows")]
+fn capture_loop_etw() {
+    if let Err(e) = etw_session::run() {
+        eprintln!(
+            "[Fluffy Core] WARNING: failed to start ETW kernel-network session ({e}). \
+             Per-process network usage will report 0 for every process until this is resolved."
+        );
+    }
+}
+
+/// Raw ETW plumbing for the `Microsoft-Windows-Kernel-Network` provider. Kept in its own
+/// module since it's almost entirely unsafe FFI against `windows_sys`'s Event Tracing and
+/// TDH bindings, and none of it is relevant to the socket-table/folding logic above.
+#[cfg(target_os = "windows")]
+mod etw_session {
+    use super::{record_packet, Proto, SocketKey};
+    use std::ffi::OsStr;
+    use std::net::{IpAddr, Ipv4Addr, Ipv6Addr};
+    use std::os::windows::ffi::OsStrExt;
+    use std::ptr;
+    use windows_sys::Win32::Foundation::ERROR_SUCCESS;
+    use windows_sys::Win32::System::Diagnostics::Etw::{
+        CloseTrace, ControlTraceW, EnableTraceEx2, OpenTraceW, ProcessTrace, StartTraceW,
+        EVENT_CONTROL_CODE_ENABLE_PROVIDER, EVENT_RECORD, EVENT_TRACE_CONTROL_STOP,
+        EVENT_TRACE_LOGFILEW, EVENT_TRACE_PROPERTIES, EVENT_TRACE_REAL_TIME_MODE,
+        PROCESS_TRACE_MODE_EVENT_RECORD, PROCESS_TRACE_MODE_REAL_TIME, TRACE_LEVEL_INFORMATION,
+        WNODE_FLAG_TRACED_GUID,
+    };
+    use windows_sys::Win32::System::Diagnostics::Tdh::{
+        TdhGetEventInformation, TdhGetProperty, TRACE_EVENT_INFO,
+    };
+    use windows_sys::core::GUID;
+
+    /// `{7DD42A49-5329-4832-8DFD-43D979153A88}` — the well-known GUID of the
+    /// Microsoft-Windows-Kernel-Network manifest provider, which emits a Send/Receive
+    /// event (IPv4 and IPv6 variants) per TCP/UDP datagram carrying PID, local/remote
+    /// address and port, and size.
+    const KERNEL_NETWORK_PROVIDER: GUID = GUID::from_u128(0x7dd42a49_5329_4832_8dfd_43d979153a88);
+
+    const SESSION_NAME: &str = "FluffyNetworkTrace";
+
+    /// `EVENT_TRACE_PROPERTIES` requires extra room after the struct for the logger
+    /// name (and log file name, unused here since we only run real-time); this mirrors
+    /// the layout every ETW controller sample allocates.
+    #[repr(C)]
+    struct TraceProperties {
+        base: EVENT_TRACE_PROPERTIES,
+        logger_name: [u16; 256],
+    }
+
+    fn encode_wide(s: &str) -> Vec<u16> {
+        OsStr::new(s).encode_wide().chain(std::iter::once(0)).collect()
+    }
+
+    fn new_trace_properties() -> TraceProperties {
+        let mut props: TraceProperties = unsafe { std::mem::zeroed() };
+        props.base.Wnode.BufferSize = std::mem::size_of::<TraceProperties>() as u32;
+        props.base.Wnode.Flags = WNODE_FLAG_TRACED_GUID;
+        props.base.Wnode.ClientContext = 1; // QPC timestamp resolution
+        props.base.LogFileMode = EVENT_TRACE_REAL_TIME_MODE;
+        props.base.LoggerNameOffset = std::mem::offset_of!(TraceProperties, logger_name) as u32;
+        props
+    }
+
+    /// Start (or reuse) a real-time trace session, enable the kernel-network provider on
+    /// it, then process events until the process exits. `ProcessTrace` blocks, so this is
+    /// meant to be called from its own thread, same as `capture_loop_pnet`'s receive loop.
+    pub fn run() -> Result<(), String> {
+        let session_name_wide = encode_wide(SESSION_NAME);
+        let mut props = new_trace_properties();
+        let mut session_handle: u64 = 0;
+
+        let start_status = unsafe {
+            StartTraceW(&mut session_handle, session_name_wide.as_ptr(), &mut props.base)
+        };
+        if start_status != ERROR_SUCCESS {
+            const ERROR_ALREADY_EXISTS: u32 = 183;
+            if start_status != ERROR_ALREADY_EXISTS {
+                return Err(format!("StartTraceW failed: error {start_status}"));
+            }
+            // A previous run (or another tool) left the session running; stop it and
+            // retry rather than failing outright, since we own the session name.
+            let mut stop_props = new_trace_properties();
+            unsafe {
+                ControlTraceW(0, session_name_wide.as_ptr(), &mut stop_props.base, EVENT_TRACE_CONTROL_STOP);
+            }
+            props = new_trace_properties();
+            let retry_status = unsafe {
+                StartTraceW(&mut session_handle, session_name_wide.as_ptr(), &mut props.base)
+            };
+            if retry_status != ERROR_SUCCESS {
+                return Err(format!("StartTraceW retry failed: error {retry_status}"));
+            }
+        }
+
+        let enable_status = unsafe {
+            EnableTraceEx2(
+                session_handle,
+                &KERNEL_NETWORK_PROVIDER,
+                EVENT_CONTROL_CODE_ENABLE_PROVIDER as u32,
+                TRACE_LEVEL_INFORMATION as u8,
+                0,
+                0,
+                0,
+                ptr::null(),
+            )
+        };
+        if enable_status != ERROR_SUCCESS {
+            return Err(format!("EnableTraceEx2 failed: error {enable_status}"));
+        }
+
+        let mut logfile: EVENT_TRACE_LOGFILEW = unsafe { std::mem::zeroed() };
+        logfile.LoggerName = session_name_wide.as_ptr() as *mut _;
+        logfile.Anonymous1.ProcessTraceMode =
+            PROCESS_TRACE_MODE_REAL_TIME | PROCESS_TRACE_MODE_EVENT_RECORD;
+        logfile.Anonymous2.EventRecordCallback = Some(on_event_record);
+
+        let trace_handle = unsafe { OpenTraceW(&mut logfile) };
+        const INVALID_PROCESSTRACE_HANDLE: u64 = u64::MAX;
+        if trace_handle == INVALID_PROCESSTRACE_HANDLE {
+            return Err("OpenTraceW failed to open the real-time session".to_string());
+        }
+
+        // Blocks, invoking `on_event_record` on this thread for every event, until the
+        // session is stopped (e.g. by another `ControlTraceW(..STOP)`/process exit).
+        let process_status = unsafe { ProcessTrace(&trace_handle, 1, ptr::null(), ptr::null()) };
+        unsafe { CloseTrace(trace_handle) };
+
+        if process_status != ERROR_SUCCESS {
+            return Err(format!("ProcessTrace exited: error {process_status}"));
+        }
+        Ok(())
+    }
+
+    /// Per-event callback handed to `OpenTraceW`. Looks up the event's schema via TDH,
+    /// pulls out the named fields the Kernel-Network templates always carry, and folds
+    /// the result into `CONNECTION_TOTALS` via `record_packet`. Unrecognized/unparseable
+    /// events (e.g. a provider version we don't expect) are dropped rather than guessed at.
+    unsafe extern "system" fn on_event_record(event_record: *mut EVENT_RECORD) {
+        let Some((proto, is_send, pid, ip, port, size)) = parse_network_event(event_record) else {
+            return;
+        };
+        let _ = pid; // resolution goes through SOCKET_TABLE, like the pnet path
+        record_packet(SocketKey { proto, ip, port }, is_send, size);
+    }
+
+    unsafe fn parse_network_event(
+        event_record: *mut EVENT_RECORD,
+    ) -> Option<(Proto, bool, u32, IpAddr, u16, u64)> {
+        let mut buffer_size: u32 = 0;
+        TdhGetEventInformation(event_record, 0, ptr::null_mut(), ptr::null_mut(), &mut buffer_size);
+        if buffer_size == 0 {
+            return None;
+        }
+        let mut buffer = vec![0u8; buffer_size as usize];
+        let info = buffer.as_mut_ptr() as *mut TRACE_EVENT_INFO;
+        if TdhGetEventInformation(event_record, 0, ptr::null_mut(), info, &mut buffer_size) != ERROR_SUCCESS {
+            return None;
+        }
+
+        let opcode_name = wide_string_at_offset(info as *const u8, (*info).OpcodeNameOffset);
+        let is_send = match opcode_name.as_deref() {
+            Some("Send") => true,
+            Some("Receive") => false,
+            // Connect/Disconnect/Accept/Retransmit/etc. carry no send/receive byte count
+            // we'd want to fold in; only the two data-transfer opcodes matter here.
+            _ => return None,
+        };
+
+        let task_name = wide_string_at_offset(info as *const u8, (*info).TaskNameOffset);
+        let proto = match task_name.as_deref() {
+            Some("TcpIp") => Proto::Tcp,
+            Some("UdpIp") => Proto::Udp,
+            _ => return None,
+        };
+
+        let pid = get_property_u32(event_record, info, "PID")?;
+        let size = get_property_u32(event_record, info, "size")? as u64;
+        let port = u16::from_be(get_property_u16(event_record, info, "sport")?);
+
+        // The templates use separate field names per address family; try v4 first since
+        // it's the common case, then fall back to v6.
+        if let Some(addr) = get_property_u32(event_record, info, "saddr") {
+            let ip = IpAddr::V4(Ipv4Addr::from(u32::from_be(addr)));
+            return Some((proto, is_send, pid, ip, port, size));
+        }
+        if let Some(addr) = get_property_bytes(event_record, info, "saddr") {
+            if addr.len() >= 16 {
+                let mut octets = [0u8; 16];
+                octets.copy_from_slice(&addr[..16]);
+                let ip = IpAddr::V6(Ipv6Addr::from(octets));
+                return Some((proto, is_send, pid, ip, port, size));
+            }
+        }
+
+        None
+    }
+
+    /// Read the UTF-16 string stored in `TRACE_EVENT_INFO` at `offset` bytes from `base`
+    /// (as all the `*NameOffset` fields do), or `None` if TDH didn't resolve one.
+    unsafe fn wide_string_at_offset(base: *const u8, offset: u32) -> Option<String> {
+        if offset == 0 {
+            return None;
+        }
+        let ptr = base.add(offset as usize) as *const u16;
+        let mut len = 0usize;
+        while *ptr.add(len) != 0 {
+            len += 1;
+        }
+        let slice = std::slice::from_raw_parts(ptr, len);
+        Some(String::from_utf16_lossy(slice))
+    }
+
+    /// Fetch a named property's raw bytes via `TdhGetProperty`, matching it by name
+    /// against the property list TDH parsed out of the event's manifest. All the fields
+    /// this module reads (PID, size, address, port) are fixed-size scalars, so a 16-byte
+    /// buffer (enough for an IPv6 address, the largest field we ask for) is always enough.
+    unsafe fn get_property_bytes(
+        event_record: *mut EVENT_RECORD,
+        info: *const TRACE_EVENT_INFO,
+        name: &str,
+    ) -> Option<Vec<u8>> {
+        let property_count = (*info).PropertyCount;
+        let descriptors = (info as *const u8).add(std::mem::size_of::<TRACE_EVENT_INFO>())
+            as *const windows_sys::Win32::System::Diagnostics::Etw::EVENT_PROPERTY_INFO;
+
+        for i in 0..property_count {
+            let prop = &*descriptors.add(i as usize);
+            let prop_name = wide_string_at_offset(info as *const u8, prop.NameOffset)?;
+            if prop_name != name {
+                continue;
+            }
+
+            let mut descriptor: windows_sys::Win32::System::Diagnostics::Etw::PROPERTY_DATA_DESCRIPTOR =
+                std::mem::zeroed();
+            descriptor.PropertyName = (info as *const u8).add(prop.NameOffset as usize) as u64;
+            descriptor.ArrayIndex = u32::MAX;
+
+            let mut out = vec![0u8; 16];
+            let status =
+                TdhGetProperty(event_record, 0, ptr::null(), 1, &mut descriptor, out.len() as u32, out.as_mut_ptr());
+            if status != ERROR_SUCCESS {
+                return None;
+            }
+            return Some(out);
+        }
+        None
+    }
+
+    unsafe fn get_property_u32(
+        event_record: *mut EVENT_RECORD,
+        info: *const TRACE_EVENT_INFO,
+        name: &str,
+    ) -> Option<u32> {
+        let bytes = get_property_bytes(event_record, info, name)?;
+        if bytes.len() < 4 {
+            return None;
+        }
+        Some(u32::from_ne_bytes(bytes[0..4].try_into().ok()?))
+    }
+
+    unsafe fn get_property_u16(
+        event_record: *mut EVENT_RECORD,
+        info: *const TRACE_EVENT_INFO,
+        name: &str,
+    ) -> Option<u16> {
+        let bytes = get_property_bytes(event_record, info, name)?;
+        if bytes.len() < 2 {
+            return None;
+        }
+        Some(u16::from_ne_bytes(bytes[0..2].try_into().ok()?))
+    }
+}
+
+/// Fallback packet observer for non-Windows targets: sniff every up, non-loopback
+/// datalink interface with pnet and resolve captured packets the same way the ETW
+/// path does, by matching local IP:port against `SOCKET_TABLE`.
+#[cfg(not(target_os = "windows"))]
+fn capture_loop_pnet() {
+    use pnet::datalink::{self, Channel::Ethernet};
+    use pnet::packet::ethernet::{EtherTypes, EthernetPacket};
+    use pnet::packet::ip::IpNextHeaderProtocols;
+    use pnet::packet::ipv4::Ipv4Packet;
+    use pnet::packet::tcp::TcpPacket;
+    use pnet::packet::udp::UdpPacket;
+    use pnet::packet::Packet;
+
+    let interfaces = datalink::interfaces();
+    for iface in interfaces.into_iter().filter(|i| i.is_up() && !i.is_loopback()) {
+        std::thread::spawn(move || {
+            let (_, mut rx) = match datalink::channel(&iface, Default::default()) {
+                Ok(Ethernet(tx, rx)) => (tx, rx),
+                _ => return,
+            };
+
+            loop {
+                let packet = match rx.next() {
+                    Ok(p) => p,
+                    Err(_) => continue,
+                };
+                let Some(eth) = EthernetPacket::new(packet) else { continue };
+                if eth.get_ethertype() != EtherTypes::Ipv4 {
+                    continue;
+                }
+                let Some(ipv4) = Ipv4Packet::new(eth.payload()) else { continue };
+                let len = ipv4.get_total_length() as u64;
+
+                let local_port = match ipv4.get_next_level_protocol() {
+                    IpNextHeaderProtocols::Tcp => TcpPacket::new(ipv4.payload())
+                        .map(|p| (Proto::Tcp, p.get_source(), p.get_destination())),
+                    IpNextHeaderProtocols::Udp => UdpPacket::new(ipv4.payload())
+                        .map(|p| (Proto::Udp, p.get_source(), p.get_destination())),
+                    _ => None,
+                };
+
+                let Some((proto, src_port, dst_port)) = local_port else { continue };
+                let src_ip = IpAddr::V4(ipv4.get_source());
+                let dst_ip = IpAddr::V4(ipv4.get_destination());
+
+                let table = SOCKET_TABLE.lock().unwrap();
+                if table.contains_key(&SocketKey { proto, ip: src_ip, port: src_port }) {
+                    record_packet(SocketKey { proto, ip: src_ip, port: src_port }, true, len);
+                } else if table.contains_key(&SocketKey { proto, ip: dst_ip, port: dst_port }) {
+                    record_packet(SocketKey { proto, ip: dst_ip, port: dst_port }, false, len);
+                }
+                // Neither side matches a known local socket: drop rather than misattribute.
+            }
+        });
     }
 }
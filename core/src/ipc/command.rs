@@ -2,11 +2,17 @@ use serde::{Deserialize, Serialize};
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
 pub enum Command {
-    KillProcess { pid: u32 },
+    KillProcess { pid: u32, force: bool, grace_ms: u64 },
+    KillByName { name: String },
     RequestCleanup,
     OpenPath { path: String },
+    OpenWith { path: String, app: String },
     NormalizeSystem,
 
+    // Threat detection
+    ScanThreats,
+    KillSuspicious { min_score: f32 },
+
     // Startup Apps
     StartupAdd { name: String, path: String },
     StartupRemove { name: String },
@@ -18,4 +24,6 @@ pub enum Command {
 
     // UI state sync
     SetUiActive { active: bool },
+    SetDnsResolveEnabled { enabled: bool },
+    StartupWatch { enabled: bool },
 }
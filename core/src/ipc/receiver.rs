@@ -1,6 +1,4 @@
 use std::collections::HashMap;
-use std::io::{BufRead, BufReader};
-use std::net::TcpListener;
 // use std::process::Command;
 use std::sync::Mutex;
 
@@ -13,30 +11,13 @@ use crate::permissions::policy::evaluate;
 
 static PENDING: Lazy<Mutex<HashMap<String, IpcCommand>>> = Lazy::new(|| Mutex::new(HashMap::new()));
 
-pub fn start_command_server(port: u16) {
-    let listener = TcpListener::bind(("127.0.0.1", port)).expect("Failed to bind command port");
-    println!("[Fluffy Core] Command server listening on port {}", port);
-
-    std::thread::spawn(move || {
-        for stream in listener.incoming() {
-            if let Ok(stream) = stream {
-                let reader = BufReader::new(stream);
-                for line in reader.lines().flatten() {
-                    println!("[Fluffy Core] Received command line: {}", line);
-                    match serde_json::from_str::<IpcCommand>(&line) {
-                        Ok(cmd) => handle_command(cmd),
-                        Err(e) => eprintln!("[Fluffy Core] Failed to parse command: {}", e),
-                    }
-                }
-            }
-        }
-    });
-}
-
 static KILL_HISTORY: Lazy<Mutex<Vec<std::time::Instant>>> = Lazy::new(|| Mutex::new(Vec::new()));
 const PROTECTED_PROCESSES: &[&str] = &["csrss.exe", "wininit.exe", "lsass.exe", "services.exe", "smss.exe", "winlogon.exe"];
 
-fn handle_command(cmd: IpcCommand) {
+/// Dispatch entry point for commands read from framed `IpcServer` connections:
+/// evaluate permissions, execute immediately if allowed, or stash as pending and
+/// broadcast a `confirm_required` notice otherwise.
+pub(crate) fn handle_command(cmd: IpcCommand) {
     match cmd {
         IpcCommand::Confirm { command_id } => {
             if let Some(original) = PENDING.lock().unwrap().remove(&command_id) {
@@ -53,6 +34,16 @@ fn handle_command(cmd: IpcCommand) {
             crate::IS_UI_ACTIVE.store(active, std::sync::atomic::Ordering::SeqCst);
         }
 
+        IpcCommand::SetDnsResolveEnabled { enabled } => {
+            println!("[Fluffy Core] Setting DNS resolution for connections to: {}", enabled);
+            crate::connections::DNS_RESOLVE_ENABLED.store(enabled, std::sync::atomic::Ordering::SeqCst);
+        }
+
+        IpcCommand::StartupWatch { enabled } => {
+            println!("[Fluffy Core] Setting startup location watcher to: {}", enabled);
+            crate::startup_watch::set_enabled(enabled);
+        }
+
         other => match evaluate(&other) {
             PermissionDecision::Allow => execute(other),
 
@@ -77,13 +68,37 @@ fn handle_command(cmd: IpcCommand) {
             PermissionDecision::Deny { reason } => {
                 println!("[DENIED] {}", reason);
             }
+
+            PermissionDecision::RequireElevation { reason } => {
+                if crate::elevation::is_elevated() {
+                    // Already running with admin rights: no need to relaunch.
+                    execute(other);
+                } else {
+                    println!("[Fluffy Core] {} — relaunching elevated", reason);
+                    let (status, details) = match crate::elevation::run_elevated(&other) {
+                        Ok(response) => serde_json::from_str::<(String, String)>(&response)
+                            .unwrap_or_else(|_| ("error".to_string(), response)),
+                        Err(e) => ("error".to_string(), e),
+                    };
+
+                    crate::ipc::server::IpcServer::broadcast_global(&crate::ipc::protocol::IpcMessage {
+                        schema_version: "1.0".to_string(),
+                        payload: serde_json::json!({
+                            "type": "execution_result",
+                            "command": format!("{:?}", other),
+                            "status": status,
+                            "details": details
+                        }),
+                    });
+                }
+            }
         },
     }
 }
 
 fn execute(cmd: IpcCommand) {
     match cmd {
-        IpcCommand::KillProcess { pid } => {
+        IpcCommand::KillProcess { pid, force, grace_ms } => {
             #[cfg(target_os = "windows")]
             {
                 use sysinfo::{Pid, System, ProcessesToUpdate};
@@ -91,13 +106,14 @@ fn execute(cmd: IpcCommand) {
 
                 let mut status = "success";
                 let mut error_msg = String::new();
+                let mut method = "forced";
 
                 // 1. Rate Limiting Check
                 let now = Instant::now();
                 let mut history = KILL_HISTORY.lock().unwrap();
                 // Remove entries older than 10 seconds
                 history.retain(|&t| now.duration_since(t) < Duration::from_secs(10));
-                
+
                 if history.len() >= 3 {
                     status = "error";
                     error_msg = "Rate limit exceeded: >3 kills in 10s".to_string();
@@ -121,23 +137,32 @@ fn execute(cmd: IpcCommand) {
                 if status == "success" {
                      // Record this attempt for rate limiting (only if we are actually proceeding)
                      history.push(now);
-                     
-                    let result = std::process::Command::new("taskkill")
-                        .args(["/PID", &pid.to_string(), "/T", "/F"])
-                        .output();
-
-                    status = match result {
-                        Ok(out) if out.status.success() => "success",
-                        Ok(out) => {
-                            let stderr = String::from_utf8_lossy(&out.stderr).to_string();
-                            error_msg = stderr.trim().to_string();
-                            "error"
-                        }
-                        Err(e) => {
-                            error_msg = e.to_string();
-                            "error"
-                        }
-                    };
+                     drop(history);
+
+                    // Escalation ladder: try a graceful close first (unless the caller
+                    // opted straight into force), only falling back to taskkill /F if
+                    // the process is still alive once the grace period elapses.
+                    if !force && try_graceful_close(pid, grace_ms) {
+                        method = "graceful";
+                    } else {
+                        method = "forced";
+                        let result = std::process::Command::new("taskkill")
+                            .args(["/PID", &pid.to_string(), "/T", "/F"])
+                            .output();
+
+                        status = match result {
+                            Ok(out) if out.status.success() => "success",
+                            Ok(out) => {
+                                let stderr = String::from_utf8_lossy(&out.stderr).to_string();
+                                error_msg = stderr.trim().to_string();
+                                "error"
+                            }
+                            Err(e) => {
+                                error_msg = e.to_string();
+                                "error"
+                            }
+                        };
+                    }
                 }
 
                 crate::ipc::server::IpcServer::broadcast_global(&crate::ipc::protocol::IpcMessage {
@@ -147,6 +172,7 @@ fn execute(cmd: IpcCommand) {
                         "command": "KillProcess",
                         "pid": pid,
                         "status": status,
+                        "method": method,
                         "error": if error_msg.is_empty() { None } else { Some(error_msg) }
                     }),
                 });
@@ -155,30 +181,20 @@ fn execute(cmd: IpcCommand) {
         IpcCommand::StartupAdd { name, path } => {
             #[cfg(target_os = "windows")]
             {
-                // PowerShell is robust for registry operations
-                let script = format!(
-                    "New-ItemProperty -Path 'HKCU:\\Software\\Microsoft\\Windows\\CurrentVersion\\Run' -Name '{}' -Value '{}' -PropertyType String -Force",
-                    name.replace("'", "''"), 
-                    path.replace("'", "''")
+                // PowerShell is robust for registry operations. $name/$path are bound
+                // params, not interpolated into the script text.
+                let result = crate::powershell::run_script(
+                    "New-ItemProperty -Path 'HKCU:\\Software\\Microsoft\\Windows\\CurrentVersion\\Run' -Name $name -Value $path -PropertyType String -Force | Out-Null",
+                    &[("name", name.as_str()), ("path", path.as_str())],
                 );
 
-                let output = std::process::Command::new("powershell")
-                    .args(["-Command", &script])
-                    .output();
-
-                let (status, error) = match output {
-                    Ok(out) if out.status.success() => ("success", None),
-                    Ok(out) => ("error", Some(String::from_utf8_lossy(&out.stderr).trim().to_string())),
-                    Err(e) => ("error", Some(e.to_string())),
-                };
-
                 crate::ipc::server::IpcServer::broadcast_global(&crate::ipc::protocol::IpcMessage {
                     schema_version: "1.0".to_string(),
                     payload: serde_json::json!({
                         "type": "execution_result",
                         "command": "StartupAdd",
-                        "status": status,
-                        "error": error
+                        "status": if result.success { "success" } else { "error" },
+                        "error": if result.success { None } else { Some(result.stderr) }
                     }),
                 });
             }
@@ -193,35 +209,23 @@ fn execute(cmd: IpcCommand) {
                 // Parse source from name e.g. "My App (HKCU)" or "script.bat (Folder)"
                 if name.ends_with("(HKCU)") {
                     let real_name = name.strip_suffix(" (HKCU)").unwrap();
-                    let script = format!(
-                        "Remove-ItemProperty -Path 'HKCU:\\Software\\Microsoft\\Windows\\CurrentVersion\\Run' -Name '{}' -Force",
-                        real_name.replace("'", "''")
+                    let result = crate::powershell::run_script(
+                        "Remove-ItemProperty -Path 'HKCU:\\Software\\Microsoft\\Windows\\CurrentVersion\\Run' -Name $name -Force",
+                        &[("name", real_name)],
                     );
-                    let output = std::process::Command::new("powershell").args(["-Command", &script]).output();
-                    if let Ok(out) = output {
-                        if !out.status.success() {
-                            status = "error";
-                            error = Some(String::from_utf8_lossy(&out.stderr).trim().to_string());
-                        }
-                    } else if let Err(e) = output {
+                    if !result.success {
                         status = "error";
-                        error = Some(e.to_string());
+                        error = Some(result.stderr);
                     }
                 } else if name.ends_with("(HKLM)") {
                     let real_name = name.strip_suffix(" (HKLM)").unwrap();
-                    let script = format!(
-                        "Remove-ItemProperty -Path 'HKLM:\\Software\\Microsoft\\Windows\\CurrentVersion\\Run' -Name '{}' -Force",
-                        real_name.replace("'", "''")
+                    let result = crate::powershell::run_script(
+                        "Remove-ItemProperty -Path 'HKLM:\\Software\\Microsoft\\Windows\\CurrentVersion\\Run' -Name $name -Force",
+                        &[("name", real_name)],
                     );
-                    let output = std::process::Command::new("powershell").args(["-Command", &script]).output();
-                    if let Ok(out) = output {
-                        if !out.status.success() {
-                            status = "error";
-                            error = Some("Failed to remove HKLM entry. Ensure Fluffy is running as Administrator.".to_string());
-                        }
-                    } else if let Err(e) = output {
+                    if !result.success {
                         status = "error";
-                        error = Some(e.to_string());
+                        error = Some("Failed to remove HKLM entry. Ensure Fluffy is running as Administrator.".to_string());
                     }
                 } else if name.ends_with("(Folder)") {
                     let real_name = name.strip_suffix(" (Folder)").unwrap();
@@ -249,11 +253,10 @@ fn execute(cmd: IpcCommand) {
                     }
                 } else {
                     // Fallback for legacy items or items without suffix (defaults to HKCU)
-                    let script = format!(
-                        "Remove-ItemProperty -Path 'HKCU:\\Software\\Microsoft\\Windows\\CurrentVersion\\Run' -Name '{}' -Force",
-                        name.replace("'", "''")
+                    let _ = crate::powershell::run_script(
+                        "Remove-ItemProperty -Path 'HKCU:\\Software\\Microsoft\\Windows\\CurrentVersion\\Run' -Name $name -Force",
+                        &[("name", name.as_str())],
                     );
-                    let _ = std::process::Command::new("powershell").args(["-Command", &script]).output();
                 }
 
                 crate::ipc::server::IpcServer::broadcast_global(&crate::ipc::protocol::IpcMessage {
@@ -277,7 +280,7 @@ fn execute(cmd: IpcCommand) {
                 // Handle registry entries only for now. Folder entries are complex to toggle.
                 if name.ends_with("(HKCU)") || name.ends_with("(HKLM)") {
                     let (_hive_path, approved_path) = if name.ends_with("(HKCU)") {
-                        ("HKCU:\\Software\\Microsoft\\Windows\\CurrentVersion\\Run", 
+                        ("HKCU:\\Software\\Microsoft\\Windows\\CurrentVersion\\Run",
                          "HKCU:\\Software\\Microsoft\\Windows\\CurrentVersion\\Explorer\\StartupApproved\\Run")
                     } else {
                         ("HKLM:\\Software\\Microsoft\\Windows\\CurrentVersion\\Run",
@@ -290,25 +293,19 @@ fn execute(cmd: IpcCommand) {
                         name.strip_suffix(" (HKLM)").unwrap()
                     };
 
-                    // Value 0x02 enabled, 0x03 disabled (binary byte array)
+                    // Value 0x02 enabled, 0x03 disabled (binary byte array). This is a
+                    // fixed literal derived from `enabled`, not user input, so it's safe
+                    // to keep in the script body rather than bind it as a param.
                     let hex_val = if enabled { "02,00,00,00,00,00,00,00,00,00,00,00" } else { "03,00,00,00,00,00,00,00,00,00,00,00" };
-                    
                     let script = format!(
-                        "Set-ItemProperty -Path '{}' -Name '{}' -Value ([byte[]]({})) -Type Binary -Force",
-                        approved_path,
-                        real_name.replace("'", "''"),
+                        "Set-ItemProperty -Path $approvedpath -Name $name -Value ([byte[]]({})) -Type Binary -Force",
                         hex_val
                     );
 
-                    let output = std::process::Command::new("powershell").args(["-Command", &script]).output();
-                    if let Ok(out) = output {
-                        if !out.status.success() {
-                            status = "error";
-                            error = Some(String::from_utf8_lossy(&out.stderr).trim().to_string());
-                        }
-                    } else if let Err(e) = output {
+                    let result = crate::powershell::run_script(&script, &[("approvedpath", approved_path), ("name", real_name)]);
+                    if !result.success {
                         status = "error";
-                        error = Some(e.to_string());
+                        error = Some(result.stderr);
                     }
                 } else {
                     status = "error";
@@ -328,83 +325,79 @@ fn execute(cmd: IpcCommand) {
         }
 
         IpcCommand::NormalizeSystem => {
-            #[cfg(target_os = "windows")]
-            {
-                let mut status = "success";
-                let mut details = "System normalization and optimization pulse complete.".to_string();
-
-                // 1. A/V Normalization (Volume 50%, Brightness 70%)
-                let av_script = "
-                    $obj = new-object -com wscript.shell; for($i=0;$i-lt 50;$i++){$obj.SendKeys([char]174)}; for($i=0;$i-lt 25;$i++){$obj.SendKeys([char]175)};
-                    $m = Get-CimInstance -Namespace root/WMI -ClassName WmiMonitorBrightnessMethods -ErrorAction SilentlyContinue;
-                    if($m){ $m | Invoke-CimMethod -MethodName WmiSetBrightness -Arguments @{ Timeout = 0; Brightness = 70 } }
-                ";
-                let _ = std::process::Command::new("powershell").args(["-Command", av_script]).output();
-
-                // 2. Comprehensive Cleanup (Temp, Prefetch, SoftwareDistribution, Recycle Bin)
-                let cleanup_script = "
-                    $paths = @(\"$env:TEMP\\*\", \"C:\\Windows\\Temp\\*\", \"C:\\Windows\\Prefetch\\*\", \"C:\\Windows\\SoftwareDistribution\\Download\\*\");
-                    foreach($p in $paths){ Remove-Item -Path $p -Recurse -Force -ErrorAction SilentlyContinue }
-                    Clear-RecycleBin -Confirm:$false -ErrorAction SilentlyContinue;
-                ";
-                let _ = std::process::Command::new("powershell").args(["-Command", cleanup_script]).output();
-
-                // 3. Cache & Network (DNS Flush)
-                let _ = std::process::Command::new("ipconfig").arg("/flushdns").output();
-
-                // 4. Memory & Performance (Trim working sets, SSD Re-trim)
-                let opt_script = "
-                    Get-Process | ForEach-Object { try { $_.Trim(); } catch {} };
-                    Optimize-Volume -DriveLetter C -ReTrim -ErrorAction SilentlyContinue;
-                ";
-                let _ = std::process::Command::new("powershell").args(["-Command", opt_script]).output();
-
-                // 5. Browser Cache Patterns (Chrome & Edge)
-                let browser_script = "
-                    $local = $env:LOCALAPPDATA;
-                    $bPaths = @(
-                        \"$local\\Google\\Chrome\\User Data\\Default\\Cache\\*\",
-                        \"$local\\Google\\Chrome\\User Data\\Default\\Code Cache\\*\",
-                        \"$local\\Microsoft\\Edge\\User Data\\Default\\Cache\\*\",
-                        \"$local\\Microsoft\\Edge\\User Data\\Default\\Code Cache\\*\"
-                    );
-                    foreach($p in $bPaths){ Remove-Item -Path $p -Recurse -Force -ErrorAction SilentlyContinue }
-                ";
-                let _ = std::process::Command::new("powershell").args(["-Command", browser_script]).output();
+            let (status, details) = run_normalize_system();
+            crate::ipc::server::IpcServer::broadcast_global(&crate::ipc::protocol::IpcMessage {
+                schema_version: "1.0".to_string(),
+                payload: serde_json::json!({
+                    "type": "execution_result",
+                    "command": "NormalizeSystem",
+                    "status": status,
+                    "details": details
+                }),
+            });
+        }
 
-                crate::ipc::server::IpcServer::broadcast_global(&crate::ipc::protocol::IpcMessage {
-                    schema_version: "1.0".to_string(),
-                    payload: serde_json::json!({
-                        "type": "execution_result",
-                        "command": "NormalizeSystem",
-                        "status": status,
-                        "details": details
-                    }),
-                });
-            }
+        IpcCommand::ScanThreats => {
+            let threats = crate::threat::latest();
+            crate::ipc::server::IpcServer::broadcast_global(&crate::ipc::protocol::IpcMessage {
+                schema_version: "1.0".to_string(),
+                payload: serde_json::json!({ "type": "threat_detected", "threats": threats }),
+            });
+        }
 
-            #[cfg(not(target_os = "windows"))]
-            {
-                let mut status = "success";
-                let mut details = "Linux system normalization initialized (Temp, Cache, and RAM pulse).".to_string();
+        IpcCommand::KillByName { name } => {
+            let launcher = crate::actions::AppLauncher::new();
+            let result = launcher.kill_by_name(&name);
+
+            crate::ipc::server::IpcServer::broadcast_global(&crate::ipc::protocol::IpcMessage {
+                schema_version: "1.0".to_string(),
+                payload: serde_json::json!({
+                    "type": "execution_result",
+                    "command": "KillByName",
+                    "status": if result.is_ok() { "success" } else { "error" },
+                    "details": result.unwrap_or_else(|e| e)
+                }),
+            });
+        }
 
-                // 1. Temp & Cache Cleanup
-                let cleanup_cmd = "rm -rf /tmp/* /var/tmp/* ~/.cache/* 2>/dev/null";
-                let _ = std::process::Command::new("sh").args(["-c", cleanup_cmd]).output();
+        IpcCommand::OpenWith { path, app } => {
+            let launcher = crate::actions::AppLauncher::new();
+            let result = launcher.open_with(std::path::Path::new(&path), &app);
+
+            crate::ipc::server::IpcServer::broadcast_global(&crate::ipc::protocol::IpcMessage {
+                schema_version: "1.0".to_string(),
+                payload: serde_json::json!({
+                    "type": "execution_result",
+                    "command": "OpenWith",
+                    "status": if result.is_ok() { "success" } else { "error" },
+                    "details": result.unwrap_or_else(|e| e)
+                }),
+            });
+        }
 
-                // 2. Memory Optimization (Drop caches if root, sync disks)
-                let mem_cmd = "sync; if [ \"$(id -u)\" -eq 0 ]; then echo 3 > /proc/sys/vm/drop_caches; fi";
-                let _ = std::process::Command::new("sh").args(["-c", mem_cmd]).output();
+        IpcCommand::KillSuspicious { min_score } => {
+            let targets: Vec<u32> = crate::threat::latest()
+                .into_iter()
+                .filter(|t| t.score >= min_score)
+                .map(|t| t.pid)
+                .collect();
 
+            if targets.is_empty() {
                 crate::ipc::server::IpcServer::broadcast_global(&crate::ipc::protocol::IpcMessage {
                     schema_version: "1.0".to_string(),
                     payload: serde_json::json!({
                         "type": "execution_result",
-                        "command": "NormalizeSystem",
-                        "status": status,
-                        "details": details
+                        "command": "KillSuspicious",
+                        "status": "success",
+                        "details": "No flagged process currently meets that score threshold"
                     }),
                 });
+            } else {
+                // Each pid still goes through KillProcess's own protected-process and
+                // rate-limit checks, and broadcasts its own execution_result.
+                for pid in targets {
+                    execute(IpcCommand::KillProcess { pid, force: false, grace_ms: 3000 });
+                }
             }
         }
 
@@ -412,3 +405,134 @@ fn execute(cmd: IpcCommand) {
     }
 }
 
+/// Ask `pid` to close itself (WM_CLOSE to its top-level windows, CTRL_BREAK_EVENT if
+/// it's a console process), then poll for up to `grace_ms` to see if it actually exits.
+/// Returns true once the process is gone; false if it's still around when time's up,
+/// in which case the caller should fall back to a forced kill.
+#[cfg(target_os = "windows")]
+fn try_graceful_close(pid: u32, grace_ms: u64) -> bool {
+    use windows_sys::Win32::Foundation::{HWND, LPARAM};
+    use windows_sys::Win32::System::Console::{GenerateConsoleCtrlEvent, CTRL_BREAK_EVENT};
+    use windows_sys::Win32::UI::WindowsAndMessaging::{EnumWindows, GetWindowThreadProcessId, PostMessageW, WM_CLOSE};
+
+    unsafe extern "system" fn enum_proc(hwnd: HWND, lparam: LPARAM) -> i32 {
+        let target_pid = lparam as u32;
+        let mut window_pid = 0u32;
+        GetWindowThreadProcessId(hwnd, &mut window_pid);
+        if window_pid == target_pid {
+            PostMessageW(hwnd, WM_CLOSE, 0, 0);
+        }
+        1 // keep enumerating; a process may own more than one top-level window
+    }
+
+    unsafe {
+        EnumWindows(Some(enum_proc), pid as LPARAM);
+        // Best-effort: only works for console apps sharing our process group, but
+        // costs nothing to try alongside WM_CLOSE.
+        let _ = GenerateConsoleCtrlEvent(CTRL_BREAK_EVENT, pid);
+    }
+
+    poll_until_gone(pid, grace_ms)
+}
+
+#[cfg(target_os = "windows")]
+fn poll_until_gone(pid: u32, grace_ms: u64) -> bool {
+    use sysinfo::{Pid, ProcessesToUpdate, System};
+    use std::time::{Duration, Instant};
+
+    let deadline = Instant::now() + Duration::from_millis(grace_ms);
+    let target_pid = Pid::from_u32(pid);
+    let mut sys = System::new();
+
+    loop {
+        sys.refresh_processes(ProcessesToUpdate::Some(&[target_pid]), true);
+        if sys.process(target_pid).is_none() {
+            return true;
+        }
+        if Instant::now() >= deadline {
+            return false;
+        }
+        std::thread::sleep(Duration::from_millis(100));
+    }
+}
+
+/// The OS-level work behind `NormalizeSystem`, split out from `execute` so it can also
+/// run inside an elevated helper process (see `crate::elevation`) without that process
+/// needing its own IPC server to broadcast through.
+fn run_normalize_system() -> (&'static str, String) {
+    #[cfg(target_os = "windows")]
+    {
+        let status = "success";
+        let details = "System normalization and optimization pulse complete.".to_string();
+
+        // 1. A/V Normalization (Volume 50%, Brightness 70%)
+        let av_script = "
+            $obj = new-object -com wscript.shell; for($i=0;$i-lt 50;$i++){$obj.SendKeys([char]174)}; for($i=0;$i-lt 25;$i++){$obj.SendKeys([char]175)};
+            $m = Get-CimInstance -Namespace root/WMI -ClassName WmiMonitorBrightnessMethods -ErrorAction SilentlyContinue;
+            if($m){ $m | Invoke-CimMethod -MethodName WmiSetBrightness -Arguments @{ Timeout = 0; Brightness = 70 } }
+        ";
+        let _ = crate::powershell::run_script(av_script, &[]);
+
+        // 2. Comprehensive Cleanup (Temp, Prefetch, SoftwareDistribution, Recycle Bin)
+        let cleanup_script = "
+            $paths = @(\"$env:TEMP\\*\", \"C:\\Windows\\Temp\\*\", \"C:\\Windows\\Prefetch\\*\", \"C:\\Windows\\SoftwareDistribution\\Download\\*\");
+            foreach($p in $paths){ Remove-Item -Path $p -Recurse -Force -ErrorAction SilentlyContinue }
+            Clear-RecycleBin -Confirm:$false -ErrorAction SilentlyContinue;
+        ";
+        let _ = crate::powershell::run_script(cleanup_script, &[]);
+
+        // 3. Cache & Network (DNS Flush)
+        let _ = std::process::Command::new("ipconfig").arg("/flushdns").output();
+
+        // 4. Memory & Performance (Trim working sets, SSD Re-trim)
+        let opt_script = "
+            Get-Process | ForEach-Object { try { $_.Trim(); } catch {} };
+            Optimize-Volume -DriveLetter C -ReTrim -ErrorAction SilentlyContinue;
+        ";
+        let _ = crate::powershell::run_script(opt_script, &[]);
+
+        // 5. Browser Cache Patterns (Chrome & Edge)
+        let browser_script = "
+            $local = $env:LOCALAPPDATA;
+            $bPaths = @(
+                \"$local\\Google\\Chrome\\User Data\\Default\\Cache\\*\",
+                \"$local\\Google\\Chrome\\User Data\\Default\\Code Cache\\*\",
+                \"$local\\Microsoft\\Edge\\User Data\\Default\\Cache\\*\",
+                \"$local\\Microsoft\\Edge\\User Data\\Default\\Code Cache\\*\"
+            );
+            foreach($p in $bPaths){ Remove-Item -Path $p -Recurse -Force -ErrorAction SilentlyContinue }
+        ";
+        let _ = crate::powershell::run_script(browser_script, &[]);
+
+        (status, details)
+    }
+
+    #[cfg(not(target_os = "windows"))]
+    {
+        let status = "success";
+        let details = "Linux system normalization initialized (Temp, Cache, and RAM pulse).".to_string();
+
+        // 1. Temp & Cache Cleanup
+        let cleanup_cmd = "rm -rf /tmp/* /var/tmp/* ~/.cache/* 2>/dev/null";
+        let _ = std::process::Command::new("sh").args(["-c", cleanup_cmd]).output();
+
+        // 2. Memory Optimization (Drop caches if root, sync disks)
+        let mem_cmd = "sync; if [ \"$(id -u)\" -eq 0 ]; then echo 3 > /proc/sys/vm/drop_caches; fi";
+        let _ = std::process::Command::new("sh").args(["-c", mem_cmd]).output();
+
+        (status, details)
+    }
+}
+
+/// Entry point for the elevated helper process (see `crate::elevation::run_as_helper`):
+/// run the privileged OS action for `cmd` and return a `(status, details)` pair
+/// serialized as JSON, to be sent back over the elevation pipe.
+pub(crate) fn run_privileged_action(cmd: IpcCommand) -> String {
+    let (status, details) = match cmd {
+        IpcCommand::NormalizeSystem => run_normalize_system(),
+        _ => ("error", "Command does not support elevated execution".to_string()),
+    };
+
+    serde_json::to_string(&(status, details)).unwrap_or_else(|_| "[\"error\",\"serialization failed\"]".to_string())
+}
+
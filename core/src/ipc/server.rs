@@ -1,28 +1,47 @@
-use std::io::Write;
+use std::io::{Read, Write};
 use std::net::{TcpListener, TcpStream};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::mpsc::{self, Sender};
 use std::sync::{Arc, Mutex};
 
+use crate::ipc::command::Command;
 use crate::ipc::protocol::IpcMessage;
 
 use once_cell::sync::Lazy;
 
 pub static GLOBAL_IPC: Lazy<Mutex<Option<IpcServer>>> = Lazy::new(|| Mutex::new(None));
 
+// Frames are length-prefixed (4-byte big-endian byte count) rather than newline-delimited,
+// since a JSON body can legitimately contain a literal newline.
+const MAX_FRAME_BYTES: u32 = 10 * 1024 * 1024;
+
+static NEXT_CLIENT_ID: AtomicU64 = AtomicU64::new(1);
+
+struct ClientHandle {
+    id: u64,
+    // Each client gets its own outbound queue, so one stalled or slow reader backs up
+    // only its own channel instead of blocking `broadcast` for every other client.
+    outbound: Sender<Vec<u8>>,
+}
+
 pub struct IpcServer {
-    clients: Arc<Mutex<Vec<TcpStream>>>,
+    clients: Arc<Mutex<Vec<ClientHandle>>>,
 }
 
 impl IpcServer {
     pub fn start(port: u16) -> Self {
         let listener = TcpListener::bind(("127.0.0.1", port)).expect("Failed to bind IPC port");
 
-        let clients = Arc::new(Mutex::new(Vec::new()));
+        let clients: Arc<Mutex<Vec<ClientHandle>>> = Arc::new(Mutex::new(Vec::new()));
         let clients_clone = clients.clone();
 
         std::thread::spawn(move || {
             for stream in listener.incoming() {
                 if let Ok(stream) = stream {
-                    clients_clone.lock().unwrap().push(stream);
+                    let clients = clients_clone.clone();
+                    // Handshake happens per-connection on its own thread, so a slow or
+                    // malicious client can't stall the accept loop for everyone else.
+                    std::thread::spawn(move || Self::accept_client(stream, clients));
                 }
             }
         });
@@ -32,15 +51,74 @@ impl IpcServer {
         server
     }
 
-    pub fn broadcast(&self, msg: &IpcMessage) {
-        let json = serde_json::to_string(msg).unwrap();
-        let mut clients = self.clients.lock().unwrap();
+    /// Authenticate the connection, then spin up its writer (outbound queue) and reader
+    /// (inbound command routing) threads. A failed handshake never registers the client,
+    /// so it never receives broadcasts and can't issue commands.
+    fn accept_client(stream: TcpStream, clients: Arc<Mutex<Vec<ClientHandle>>>) {
+        let mut reader_stream = match stream.try_clone() {
+            Ok(s) => s,
+            Err(_) => return,
+        };
+
+        if !authenticate(&mut reader_stream) {
+            return;
+        }
+
+        let id = NEXT_CLIENT_ID.fetch_add(1, Ordering::SeqCst);
+        let (tx, rx) = mpsc::channel::<Vec<u8>>();
+
+        let mut writer_stream = stream;
+        std::thread::spawn(move || {
+            for frame in rx {
+                if write_frame(&mut writer_stream, &frame).is_err() {
+                    break;
+                }
+            }
+        });
 
-        clients.retain_mut(|stream| {
-            stream.write_all(json.as_bytes()).is_ok() && stream.write_all(b"\n").is_ok()
+        clients.lock().unwrap().push(ClientHandle { id, outbound: tx.clone() });
+
+        // Route every frame through the receiver's confirm/evaluate/execute pipeline
+        // (`receiver::handle_command`).
+        std::thread::spawn(move || {
+            loop {
+                let frame = match read_frame(&mut reader_stream) {
+                    Ok(Some(frame)) => frame,
+                    _ => break,
+                };
+
+                match serde_json::from_slice::<Command>(&frame) {
+                    Ok(cmd) => crate::ipc::receiver::handle_command(cmd),
+                    Err(e) => {
+                        let _ = send_to(&tx, &IpcMessage {
+                            schema_version: "1.0".to_string(),
+                            payload: serde_json::json!({
+                                "type": "error",
+                                "details": format!("Malformed command: {}", e)
+                            }),
+                        });
+                    }
+                }
+            }
+
+            clients.lock().unwrap().retain(|c| c.id != id);
         });
     }
 
+    pub fn broadcast(&self, msg: &IpcMessage) {
+        let frame = match serde_json::to_vec(msg) {
+            Ok(frame) => frame,
+            Err(_) => return,
+        };
+
+        let clients = self.clients.lock().unwrap();
+        for client in clients.iter() {
+            // The channel is unbounded, so a send only fails once this client's writer
+            // is gone; its reader thread notices and evicts it, not us.
+            let _ = client.outbound.send(frame.clone());
+        }
+    }
+
     pub fn broadcast_global(msg: &IpcMessage) {
         if let Some(server) = &*GLOBAL_IPC.lock().unwrap() {
             server.broadcast(msg);
@@ -55,3 +133,82 @@ impl Clone for IpcServer {
         }
     }
 }
+
+fn send_to(tx: &Sender<Vec<u8>>, msg: &IpcMessage) -> Result<(), ()> {
+    let frame = serde_json::to_vec(msg).map_err(|_| ())?;
+    tx.send(frame).map_err(|_| ())
+}
+
+fn write_frame(stream: &mut TcpStream, body: &[u8]) -> std::io::Result<()> {
+    stream.write_all(&(body.len() as u32).to_be_bytes())?;
+    stream.write_all(body)
+}
+
+fn read_frame(stream: &mut TcpStream) -> std::io::Result<Option<Vec<u8>>> {
+    let mut len_buf = [0u8; 4];
+    if let Err(e) = stream.read_exact(&mut len_buf) {
+        return if e.kind() == std::io::ErrorKind::UnexpectedEof {
+            Ok(None)
+        } else {
+            Err(e)
+        };
+    }
+
+    let len = u32::from_be_bytes(len_buf);
+    if len > MAX_FRAME_BYTES {
+        return Err(std::io::Error::new(std::io::ErrorKind::InvalidData, "frame too large"));
+    }
+
+    let mut body = vec![0u8; len as usize];
+    stream.read_exact(&mut body)?;
+    Ok(Some(body))
+}
+
+/// The first frame on every connection must be the shared secret, loaded (or generated)
+/// on the first handshake and persisted to disk so a legitimate local client can read it back.
+/// The server only ever binds loopback, but any local process can still connect to a
+/// loopback port, so this is the floor for "a client we actually trust" rather than
+/// relying on the port alone.
+fn authenticate(stream: &mut TcpStream) -> bool {
+    matches!(read_frame(stream), Ok(Some(frame)) if constant_time_eq(&frame, shared_secret().as_bytes()))
+}
+
+/// Compare two byte strings without short-circuiting on the first mismatch, so the
+/// comparison time doesn't leak how many leading bytes of a guessed token were correct.
+fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+    if a.len() != b.len() {
+        return false;
+    }
+    a.iter().zip(b.iter()).fold(0u8, |acc, (&x, &y)| acc | (x ^ y)) == 0
+}
+
+static SHARED_SECRET: Lazy<String> = Lazy::new(load_or_create_shared_secret);
+
+fn shared_secret() -> &'static str {
+    &SHARED_SECRET
+}
+
+fn load_or_create_shared_secret() -> String {
+    let path = secret_path();
+
+    if let Ok(existing) = std::fs::read_to_string(&path) {
+        let trimmed = existing.trim();
+        if !trimmed.is_empty() {
+            return trimmed.to_string();
+        }
+    }
+
+    let token = uuid::Uuid::new_v4().to_string();
+    if let Some(parent) = path.parent() {
+        let _ = std::fs::create_dir_all(parent);
+    }
+    let _ = std::fs::write(&path, &token);
+    token
+}
+
+fn secret_path() -> std::path::PathBuf {
+    dirs::data_local_dir()
+        .unwrap_or_else(|| std::path::PathBuf::from("."))
+        .join("Fluffy")
+        .join("ipc_token")
+}
@@ -1,12 +1,22 @@
+mod connections;
+mod elevation;
 mod etw;
 mod ipc;
 mod permissions;
 mod actions;
-
+mod powershell;
+mod service;
+mod startup_watch;
+mod supervisor;
+mod telemetry;
+mod threat;
+
+use connections::ConnectionInfo;
 use etw::NetworkMonitor;
+use supervisor::Supervisor;
+use telemetry::mqtt::{MqttConfig, MqttPublisher};
 
 use ipc::protocol::IpcMessage;
-use ipc::receiver::start_command_server;
 use ipc::server::IpcServer;
 
 use serde::Serialize;
@@ -19,7 +29,7 @@ use std::{
     thread,
     time::{Duration, SystemTime, UNIX_EPOCH},
 };
-use sysinfo::{Networks, ProcessesToUpdate, System};
+use sysinfo::{Components, Disks, Networks, ProcessesToUpdate, System};
 pub static IS_UI_ACTIVE: AtomicBool = AtomicBool::new(false);
 
 type CpuHistory = HashMap<u32, f32>;
@@ -64,12 +74,32 @@ struct NetworkStats {
     status: String, // "wifi", "ethernet", "offline"
 }
 
+#[derive(Serialize)]
+struct ComponentStats {
+    label: String,
+    temperature_c: f32,
+    max_c: f32,
+    critical_c: Option<f32>,
+}
+
+#[derive(Serialize)]
+struct DiskStats {
+    mount_point: String,
+    total_mb: u64,
+    available_mb: u64,
+    read_kb: u64,
+    written_kb: u64,
+}
+
 #[derive(Serialize)]
 struct SystemStats {
     ram: RamStats,
     cpu: CpuStats,
     network: NetworkStats,
     processes: ProcessStats,
+    connections: Vec<ConnectionInfo>,
+    components: Vec<ComponentStats>,
+    disks: Vec<DiskStats>,
 }
 
 #[derive(Serialize)]
@@ -81,11 +111,11 @@ struct FluffyMessage {
     active_sessions: u32,
 }
 
-#[derive(Serialize)]
-struct StartupApp {
-    name: String,
-    command: String,
-    enabled: bool,
+#[derive(Serialize, Clone)]
+pub(crate) struct StartupApp {
+    pub(crate) name: String,
+    pub(crate) command: String,
+    pub(crate) enabled: bool,
 }
 
 fn kib_to_mb(kib: u64) -> u64 {
@@ -100,7 +130,7 @@ fn unix_timestamp() -> u64 {
 }
 
 #[cfg(target_os = "windows")]
-fn get_startup_entries() -> Vec<StartupApp> {
+pub(crate) fn get_startup_entries() -> Vec<StartupApp> {
     use std::ptr;
     use windows_sys::Win32::System::Registry::{
         RegCloseKey, RegEnumValueW, RegOpenKeyExW, HKEY_CURRENT_USER, HKEY_LOCAL_MACHINE, KEY_READ,
@@ -251,7 +281,7 @@ fn encode_wide(s: &str) -> Vec<u16> {
 }
 
 #[cfg(not(target_os = "windows"))]
-fn get_startup_entries() -> Vec<StartupApp> {
+pub(crate) fn get_startup_entries() -> Vec<StartupApp> {
     Vec::new()
 }
 
@@ -308,72 +338,74 @@ fn collect_processes(system: &System, cpu_history: &mut CpuHistory) -> Vec<Proce
         .collect()
 }
 
-fn spawn_listener() {
-    println!("[Fluffy Core] Spawning Brain...");
-
+fn spawn_listener_child() -> std::io::Result<std::process::Child> {
     let ui_dir = "../brain";
 
     #[cfg(target_os = "windows")]
-    let res = std::process::Command::new("cmd")
+    let child = std::process::Command::new("cmd")
         .args(["/C", "python listener.py"])
         .current_dir(ui_dir)
         .spawn();
 
     #[cfg(not(target_os = "windows"))]
-    let res = std::process::Command::new("python")
+    let child = std::process::Command::new("python")
         .args(["listener.py"])
         .current_dir(ui_dir)
         .spawn();
 
-    if let Err(e) = res {
-        eprintln!(
-            "[Fluffy Core] Failed to spawn brain: {}. Make sure you are running core from its directory and python is installed.",
-            e
-        );
-    }
+    child
 }
 
-fn spawn_ui() {
-    println!("[Fluffy Core] Spawning UI Dashboard...");
-    println!(
-        "[Fluffy Core] NOTE: First boot or changes will trigger UI compilation (approx. 1-2 minutes)."
-    );
-    println!(
-        "[Fluffy Core] Please do not close the terminal until the dashboard window appearing."
-    );
+fn spawn_listener() -> Supervisor {
+    println!("[Fluffy Core] Spawning Brain...");
+    Supervisor::new("Brain", spawn_listener_child)
+}
+
+fn spawn_ui_child() -> std::io::Result<std::process::Child> {
     let ui_dir = "../ui/tauri";
 
     #[cfg(target_os = "windows")]
-    let res = std::process::Command::new("cmd")
+    let child = std::process::Command::new("cmd")
         .args(["/C", "npm run tauri dev"])
         .current_dir(ui_dir)
         .spawn();
 
     #[cfg(not(target_os = "windows"))]
-    let res = std::process::Command::new("npm")
+    let child = std::process::Command::new("npm")
         .args(["run", "tauri", "dev"])
         .current_dir(ui_dir)
         .spawn();
 
-    if let Err(e) = res {
-        eprintln!(
-            "[Fluffy Core] Failed to spawn UI: {}. Make sure you are running core from its directory and npm is installed.",
-            e
-        );
-    }
+    child
 }
 
-fn main() {
-    let ipc = IpcServer::start(9001);
-    start_command_server(9002);
+fn spawn_ui() -> Supervisor {
+    println!("[Fluffy Core] Spawning UI Dashboard...");
+    println!(
+        "[Fluffy Core] NOTE: First boot or changes will trigger UI compilation (approx. 1-2 minutes)."
+    );
+    println!(
+        "[Fluffy Core] Please do not close the terminal until the dashboard window appearing."
+    );
+    Supervisor::new("UI Dashboard", spawn_ui_child)
+}
 
-    // 🌐 Start ETW Network Monitor (Requires Admin)
-    NetworkMonitor::start();
-    
-    // 👂 Start Brain Listener
-    spawn_listener();
-    // 🚀 Launch UI Dashboard automatically
-    spawn_ui();
+fn main() {
+    let args: Vec<String> = std::env::args().collect();
+    match args.get(1).map(String::as_str) {
+        Some("install") => return service::install(),
+        Some("uninstall") => return service::uninstall(),
+        Some("service") => return service::run_dispatcher(),
+        Some(elevation::ELEVATED_EXEC_FLAG) => {
+            let pipe_name = args.get(2).cloned().unwrap_or_default();
+            let cmd_json = args.get(3).cloned().unwrap_or_default();
+            return match serde_json::from_str::<ipc::command::Command>(&cmd_json) {
+                Ok(cmd) => elevation::run_as_helper(&pipe_name, cmd, ipc::receiver::run_privileged_action),
+                Err(e) => eprintln!("[Fluffy Core] Elevated helper got an invalid command: {}", e),
+            };
+        }
+        _ => {}
+    }
 
     let running = Arc::new(AtomicBool::new(true));
     let r = running.clone();
@@ -384,20 +416,109 @@ fn main() {
     })
     .expect("Failed to set Ctrl+C handler");
 
+    run(running, true);
+}
+
+/// The actual monitoring/broadcast loop, factored out of `main` so it can run either
+/// in a normal foreground process or under the Windows Service Control Manager
+/// (see `service::run_dispatcher`), which supplies its own `running` flag wired to
+/// `SERVICE_CONTROL_STOP` instead of Ctrl+C.
+///
+/// `foreground` distinguishes the two: a Windows service runs under `LocalSystem` in
+/// Session 0, which can't render a window, so the Tauri dashboard is only spawned when
+/// we're running interactively. The Python brain listener is headless either way, so it
+/// still starts under both.
+fn run(running: Arc<AtomicBool>, foreground: bool) {
+    let ipc = IpcServer::start(9001);
+    startup_watch::start();
+
+    // 🌐 Start ETW Network Monitor (Requires Admin)
+    NetworkMonitor::start();
+
+    // 📡 Optional MQTT export, configured via FLUFFY_MQTT_HOST
+    let mqtt = MqttConfig::from_env().map(MqttPublisher::start);
+    if mqtt.is_some() {
+        println!("[Fluffy Core] MQTT telemetry export enabled.");
+    }
+
+    // 👂 Start Brain Listener
+    let mut brain = spawn_listener();
+    // 🚀 Launch UI Dashboard automatically — only in the foreground, since a service
+    // running in Session 0 has no desktop for `npm run tauri dev` to open a window on,
+    // and Supervisor would otherwise keep restarting a dev server that can never work.
+    let mut ui = foreground.then(spawn_ui);
+
     let mut system = System::new_all();
     let mut networks = Networks::new_with_refreshed_list();
+    let mut components = Components::new_with_refreshed_list();
+    let mut disks = Disks::new_with_refreshed_list();
     let mut cpu_history = CpuHistory::new();
 
     while running.load(Ordering::SeqCst) {
+        brain.tick();
+        if let Some(ui) = &mut ui {
+            ui.tick();
+        }
+
+        // Reap launched processes that have exited, regardless of whether the UI is
+        // active, so TRACKED never grows unbounded and finished children don't sit
+        // around as zombies.
+        actions::launcher::reap_finished();
+
         if IS_UI_ACTIVE.load(Ordering::SeqCst) {
             system.refresh_memory();
             system.refresh_cpu_all();
             system.refresh_processes(ProcessesToUpdate::All, true);
             networks.refresh(true);
+            components.refresh(true);
+            disks.refresh(true);
+
+            let component_stats: Vec<ComponentStats> = components
+                .iter()
+                .map(|c| ComponentStats {
+                    label: c.label().to_string(),
+                    temperature_c: c.temperature().unwrap_or(0.0),
+                    max_c: c.max().unwrap_or(0.0),
+                    critical_c: c.critical(),
+                })
+                .collect();
+
+            let disk_stats: Vec<DiskStats> = disks
+                .iter()
+                .map(|d| {
+                    let usage = d.usage();
+                    DiskStats {
+                        mount_point: d.mount_point().to_string_lossy().into_owned(),
+                        total_mb: kib_to_mb(d.total_space()),
+                        available_mb: kib_to_mb(d.available_space()),
+                        read_kb: usage.read_bytes / 1024,
+                        written_kb: usage.written_bytes / 1024,
+                    }
+                })
+                .collect();
 
             let mut processes = collect_processes(&system, &mut cpu_history);
             processes.sort_by(|a, b| b.ram_mb.cmp(&a.ram_mb));
 
+            let threat_samples: Vec<threat::ProcessSample> = processes
+                .iter()
+                .map(|p| threat::ProcessSample {
+                    pid: p.pid,
+                    name: p.name.clone(),
+                    parent_pid: p.parent_pid,
+                    working_set_kb: p.ram_mb * 1024,
+                })
+                .collect();
+            let threats = threat::score_processes(&threat_samples);
+            threat::set_latest(threats.clone());
+            if !threats.is_empty() {
+                println!("[Fluffy Core] {} process(es) flagged as suspicious", threats.len());
+                ipc.broadcast(&IpcMessage {
+                    schema_version: "1.0".to_string(),
+                    payload: serde_json::json!({ "type": "threat_detected", "threats": threats }),
+                });
+            }
+
             let total_mb = kib_to_mb(system.total_memory());
             let free_mb = kib_to_mb(system.available_memory());
             let used_mb = total_mb - free_mb;
@@ -445,7 +566,7 @@ fn main() {
                 processes.len()
             );
             let message = FluffyMessage {
-                schema_version: "1.0",
+                schema_version: "1.1",
                 timestamp: unix_timestamp(),
                 system: SystemStats {
                     ram: RamStats {
@@ -464,13 +585,23 @@ fn main() {
                         status: connection_type.to_string(),
                     },
                     processes: ProcessStats { top_ram: processes },
+                    connections: connections::collect_connections(),
+                    components: component_stats,
+                    disks: disk_stats,
                 },
                 persistence: get_startup_entries(),
-                active_sessions: 1, // Hardcoded: UI is active if we are here
+                active_sessions: brain.is_alive() as u32
+                    + ui.as_mut().map(Supervisor::is_alive).unwrap_or(false) as u32,
             };
 
             let payload = serde_json::to_value(&message).unwrap();
 
+            if let Some(publisher) = &mqtt {
+                if let Ok(json) = serde_json::to_string(&message) {
+                    publisher.publish(&json);
+                }
+            }
+
             ipc.broadcast(&IpcMessage {
                 schema_version: "1.0".to_string(),
                 payload,
@@ -486,6 +617,14 @@ fn main() {
         }
     }
 
+    // Terminate the supervised children gracefully before exiting, instead of
+    // orphaning them the way a bare Command::spawn() would.
+    println!("[Fluffy Core] Shutting down Brain and UI Dashboard...");
+    brain.shutdown();
+    if let Some(mut ui) = ui {
+        ui.shutdown();
+    }
+
     // Broadcast shutdown signal
     let shutdown_payload = serde_json::json!({
         "type": "shutdown",
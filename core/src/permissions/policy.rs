@@ -11,11 +11,15 @@ pub fn evaluate(cmd: &Command) -> PermissionDecision {
         }
 
         // Killing processes is dangerous
-        &Command::KillProcess { pid } => {
+        &Command::KillProcess { pid, .. } => {
             if pid < 100 {
                 PermissionDecision::Deny {
                     reason: "System process protection".into(),
                 }
+            } else if crate::actions::launcher::is_tracked(pid) {
+                // Fluffy launched this process itself, so killing it carries none of
+                // the "what else might this be" risk of an arbitrary system pid
+                PermissionDecision::Allow
             } else {
                 PermissionDecision::RequireConfirmation {
                     reason: "Killing a process may cause data loss".into(),
@@ -23,6 +27,11 @@ pub fn evaluate(cmd: &Command) -> PermissionDecision {
             }
         }
 
+        // By construction this can only ever match a process Fluffy itself launched
+        // (it's resolved against the tracked-process registry, not the full process
+        // list), so it gets the same low-friction treatment as a tracked KillProcess
+        &Command::KillByName { .. } => PermissionDecision::Allow,
+
         // Cleanup is impactful
         &Command::RequestCleanup => {
             PermissionDecision::RequireConfirmation {
@@ -33,19 +42,91 @@ pub fn evaluate(cmd: &Command) -> PermissionDecision {
         // Safe operation
         &Command::OpenPath { .. } => PermissionDecision::Allow,
 
-        // System actions - Direct allow for the dashboard experience
+        // Opening a file with an explicitly chosen app is as safe as OpenPath for
+        // ordinary documents under the user's home, but running an executable/script
+        // this way is no different from launching it directly, so it gets the same
+        // confirmation gate
+        &Command::OpenWith { ref path, .. } => {
+            let path = std::path::Path::new(path);
+            if is_executable_or_script(path) {
+                PermissionDecision::RequireConfirmation {
+                    reason: "Opening an executable or script may run arbitrary code".into(),
+                }
+            } else if is_under_home(path) {
+                PermissionDecision::Allow
+            } else {
+                PermissionDecision::RequireConfirmation {
+                    reason: "File is outside the user's home directory".into(),
+                }
+            }
+        }
+
+        // System normalization touches protected directories (SoftwareDistribution,
+        // Prefetch, volume optimization) that Windows itself gates behind admin rights
         &Command::NormalizeSystem => {
-            PermissionDecision::Allow
+            PermissionDecision::RequireElevation {
+                reason: "System normalization requires administrator privileges".into(),
+            }
+        }
+
+        // Read-only: just reports whatever the background scoring loop has flagged
+        &Command::ScanThreats => PermissionDecision::Allow,
+
+        // Killing suspicious processes is the same risk class as KillProcess; the
+        // protected-process and rate-limit checks still apply per-pid at execution time
+        &Command::KillSuspicious { .. } => {
+            PermissionDecision::RequireConfirmation {
+                reason: "Killing suspicious processes may affect legitimate software".into(),
+            }
         }
 
-        // Startup App Management
-        &Command::StartupAdd { .. } | &Command::StartupRemove { .. } => {
+        // StartupAdd always writes to HKCU (see ipc::receiver), so it's never a
+        // privileged write and just needs the usual confirmation
+        &Command::StartupAdd { .. } => {
             PermissionDecision::RequireConfirmation {
                 reason: "Modifying startup applications affects system boot".into(),
             }
         }
 
+        // StartupRemove/StartupToggle carry the source tag `get_startup_entries`
+        // applies to `name` (e.g. "My App (HKLM)"); an (HKLM) entry lives under
+        // HKEY_LOCAL_MACHINE, which Windows itself gates behind administrator rights,
+        // the same reasoning `NormalizeSystem` already gets elevation for
+        &Command::StartupRemove { ref name } | &Command::StartupToggle { ref name, .. } => {
+            if name.ends_with("(HKLM)") {
+                PermissionDecision::RequireElevation {
+                    reason: "Modifying an HKLM startup entry requires administrator privileges".into(),
+                }
+            } else {
+                PermissionDecision::RequireConfirmation {
+                    reason: "Modifying startup applications affects system boot".into(),
+                }
+            }
+        }
+
         // UI state sync is always allowed
         &Command::SetUiActive { .. } => PermissionDecision::Allow,
+
+        // Toggling DNS resolution only affects how connections are displayed
+        &Command::SetDnsResolveEnabled { .. } => PermissionDecision::Allow,
+
+        // Toggling the startup watcher doesn't change anything on disk/registry
+        &Command::StartupWatch { .. } => PermissionDecision::Allow,
+    }
+}
+
+const EXECUTABLE_EXTENSIONS: &[&str] = &["exe", "msi", "bat", "cmd", "ps1", "sh"];
+
+fn is_executable_or_script(path: &std::path::Path) -> bool {
+    path.extension()
+        .and_then(|e| e.to_str())
+        .map(|e| EXECUTABLE_EXTENSIONS.contains(&e.to_lowercase().as_str()))
+        .unwrap_or(false)
+}
+
+fn is_under_home(path: &std::path::Path) -> bool {
+    match dirs::home_dir() {
+        Some(home) => path.starts_with(home),
+        None => false,
     }
 }
@@ -0,0 +1,195 @@
+//! Structured PowerShell execution: runs a script body as a temporary `.ps1` file with
+//! parameters bound through a `param()` block and passed as real process arguments
+//! (never interpolated into the script text), enforces a timeout, captures
+//! stdout/stderr/exit code, and appends one line per invocation to a rotating log so
+//! every system change Fluffy makes leaves an audit trail.
+
+use std::io::{Read, Write};
+use std::path::PathBuf;
+use std::process::{Child, Command, Stdio};
+use std::sync::mpsc;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+const DEFAULT_TIMEOUT: Duration = Duration::from_secs(30);
+const MAX_LOG_BYTES: u64 = 1024 * 1024;
+
+#[derive(Debug, Clone)]
+pub struct ScriptResult {
+    pub success: bool,
+    pub stdout: String,
+    pub stderr: String,
+    pub exit_code: Option<i32>,
+}
+
+/// Run `body` with `params` bound as named `param()` arguments, using the default 30s
+/// timeout. `params` are `(name, value)` pairs; `name` becomes `$name` inside the
+/// script and `value` is passed as a real argument, e.g. `-name "value"`.
+pub fn run_script(body: &str, params: &[(&str, &str)]) -> ScriptResult {
+    run_script_with_timeout(body, params, DEFAULT_TIMEOUT)
+}
+
+/// Same as `run_script`, but with a caller-chosen timeout instead of the default.
+pub fn run_script_with_timeout(body: &str, params: &[(&str, &str)], timeout: Duration) -> ScriptResult {
+    let started = Instant::now();
+    let result = execute(body, params, timeout);
+    log_invocation(body, params, &result, started.elapsed());
+    result
+}
+
+fn execute(body: &str, params: &[(&str, &str)], timeout: Duration) -> ScriptResult {
+    let param_block = if params.is_empty() {
+        String::new()
+    } else {
+        let names: Vec<String> = params.iter().map(|(name, _)| format!("[string]${}", name)).collect();
+        format!("param({})\n", names.join(", "))
+    };
+    let script_path = match write_temp_script(&format!("{}{}", param_block, body)) {
+        Ok(path) => path,
+        Err(e) => {
+            return ScriptResult {
+                success: false,
+                stdout: String::new(),
+                stderr: format!("Failed to write script file: {}", e),
+                exit_code: None,
+            }
+        }
+    };
+
+    let mut command = Command::new("powershell");
+    command
+        .args(["-NoProfile", "-NonInteractive", "-ExecutionPolicy", "Bypass", "-File"])
+        .arg(&script_path);
+    for (name, value) in params {
+        command.arg(format!("-{}", name));
+        command.arg(value);
+    }
+    command.stdout(Stdio::piped()).stderr(Stdio::piped());
+
+    let result = match command.spawn() {
+        Ok(child) => wait_with_timeout(child, timeout),
+        Err(e) => ScriptResult {
+            success: false,
+            stdout: String::new(),
+            stderr: format!("Failed to launch powershell: {}", e),
+            exit_code: None,
+        },
+    };
+
+    let _ = std::fs::remove_file(&script_path);
+    result
+}
+
+/// Drain stdout/stderr on background threads while polling `try_wait`, so a chatty
+/// script can't deadlock on a full pipe buffer, and kill the process if `timeout`
+/// elapses before it exits on its own.
+fn wait_with_timeout(mut child: Child, timeout: Duration) -> ScriptResult {
+    let stdout_pipe = child.stdout.take();
+    let (stdout_tx, stdout_rx) = mpsc::channel();
+    std::thread::spawn(move || {
+        let mut buf = String::new();
+        if let Some(mut pipe) = stdout_pipe {
+            let _ = pipe.read_to_string(&mut buf);
+        }
+        let _ = stdout_tx.send(buf);
+    });
+
+    let stderr_pipe = child.stderr.take();
+    let (stderr_tx, stderr_rx) = mpsc::channel();
+    std::thread::spawn(move || {
+        let mut buf = String::new();
+        if let Some(mut pipe) = stderr_pipe {
+            let _ = pipe.read_to_string(&mut buf);
+        }
+        let _ = stderr_tx.send(buf);
+    });
+
+    let deadline = Instant::now() + timeout;
+    loop {
+        match child.try_wait() {
+            Ok(Some(status)) => {
+                let stdout = stdout_rx.recv_timeout(Duration::from_secs(2)).unwrap_or_default();
+                let stderr = stderr_rx.recv_timeout(Duration::from_secs(2)).unwrap_or_default();
+                return ScriptResult {
+                    success: status.success(),
+                    stdout,
+                    stderr,
+                    exit_code: status.code(),
+                };
+            }
+            Ok(None) => {
+                if Instant::now() >= deadline {
+                    let _ = child.kill();
+                    let _ = child.wait();
+                    return ScriptResult {
+                        success: false,
+                        stdout: String::new(),
+                        stderr: format!("Script timed out after {:?}", timeout),
+                        exit_code: None,
+                    };
+                }
+                std::thread::sleep(Duration::from_millis(50));
+            }
+            Err(e) => {
+                return ScriptResult {
+                    success: false,
+                    stdout: String::new(),
+                    stderr: format!("Failed to wait on powershell process: {}", e),
+                    exit_code: None,
+                };
+            }
+        }
+    }
+}
+
+fn write_temp_script(script: &str) -> std::io::Result<PathBuf> {
+    let path = std::env::temp_dir().join(format!("fluffy-ps-{}.ps1", uuid::Uuid::new_v4().simple()));
+    let mut file = std::fs::File::create(&path)?;
+    file.write_all(script.as_bytes())?;
+    Ok(path)
+}
+
+fn log_path() -> PathBuf {
+    let dir = dirs::data_local_dir()
+        .unwrap_or_else(std::env::temp_dir)
+        .join("Fluffy")
+        .join("logs");
+    let _ = std::fs::create_dir_all(&dir);
+    dir.join("powershell.log")
+}
+
+// Past this size, move the log aside rather than letting it grow forever; we only
+// ever keep the current file plus one rotated-out predecessor.
+fn rotate_if_needed(path: &PathBuf) {
+    if let Ok(meta) = std::fs::metadata(path) {
+        if meta.len() > MAX_LOG_BYTES {
+            let _ = std::fs::rename(path, path.with_extension("log.old"));
+        }
+    }
+}
+
+fn log_invocation(body: &str, params: &[(&str, &str)], result: &ScriptResult, duration: Duration) {
+    let path = log_path();
+    rotate_if_needed(&path);
+
+    let summary = body.lines().next().unwrap_or("").trim();
+    let param_summary: Vec<String> = params.iter().map(|(k, v)| format!("{}={}", k, v)).collect();
+    let timestamp = SystemTime::now().duration_since(UNIX_EPOCH).unwrap_or_default().as_secs();
+
+    let mut line = format!(
+        "{} | {}ms | {} | exit={:?} | params=[{}] | {}",
+        timestamp,
+        duration.as_millis(),
+        if result.success { "success" } else { "error" },
+        result.exit_code,
+        param_summary.join(", "),
+        summary,
+    );
+    if !result.success {
+        line.push_str(&format!(" | stderr=\"{}\"", result.stderr.trim()));
+    }
+    line.push('\n');
+
+    if let Ok(mut file) = std::fs::OpenOptions::new().create(true).append(true).open(&path) {
+        let _ = file.write_all(line.as_bytes());
+    }
+}
@@ -0,0 +1,162 @@
+//! Windows Service Control Manager integration: `fluffy-core.exe install|uninstall`
+//! register/unregister the service, and `fluffy-core.exe service` is the entry point
+//! the SCM itself launches. Everything else (no args) runs in the foreground as a
+//! normal process, unchanged from before this module existed.
+
+#[cfg(target_os = "windows")]
+mod windows {
+    use std::ffi::OsString;
+    use std::sync::atomic::{AtomicBool, Ordering};
+    use std::sync::Arc;
+    use std::time::Duration;
+    use windows_service::service::{
+        ServiceAccess, ServiceControl, ServiceControlAccept, ServiceErrorControl, ServiceExitCode,
+        ServiceInfo, ServiceStartType, ServiceState, ServiceStatus, ServiceType,
+    };
+    use windows_service::service_control_handler::{self, ServiceControlHandlerResult};
+    use windows_service::service_manager::{ServiceManager, ServiceManagerAccess};
+    use windows_service::{define_windows_service, service_dispatcher};
+
+    const SERVICE_NAME: &str = "FluffyCoreService";
+    const SERVICE_DISPLAY_NAME: &str = "Fluffy Assistant Core";
+    const SERVICE_TYPE: ServiceType = ServiceType::OWN_PROCESS;
+
+    define_windows_service!(ffi_service_main, service_main);
+
+    pub fn install() {
+        if let Err(e) = install_inner() {
+            eprintln!("[Fluffy Core] Failed to install service: {:?}", e);
+        } else {
+            println!("[Fluffy Core] Service installed: {}", SERVICE_NAME);
+        }
+    }
+
+    fn install_inner() -> windows_service::Result<()> {
+        let manager = ServiceManager::local_computer(None::<&str>, ServiceManagerAccess::CREATE_SERVICE)?;
+        let exe_path = std::env::current_exe().map_err(windows_service::Error::Winapi)?;
+
+        let service_info = ServiceInfo {
+            name: OsString::from(SERVICE_NAME),
+            display_name: OsString::from(SERVICE_DISPLAY_NAME),
+            service_type: SERVICE_TYPE,
+            start_type: ServiceStartType::AutoStart,
+            error_control: ServiceErrorControl::Normal,
+            executable_path: exe_path,
+            launch_arguments: vec![OsString::from("service")],
+            dependencies: vec![],
+            account_name: None, // Run as LocalSystem
+            account_password: None,
+        };
+
+        let service = manager.create_service(&service_info, ServiceAccess::CHANGE_CONFIG)?;
+        service.set_description("Runs the Fluffy Assistant system monitoring core in the background.")?;
+        Ok(())
+    }
+
+    pub fn uninstall() {
+        if let Err(e) = uninstall_inner() {
+            eprintln!("[Fluffy Core] Failed to uninstall service: {:?}", e);
+        } else {
+            println!("[Fluffy Core] Service uninstalled: {}", SERVICE_NAME);
+        }
+    }
+
+    fn uninstall_inner() -> windows_service::Result<()> {
+        let manager = ServiceManager::local_computer(None::<&str>, ServiceManagerAccess::CONNECT)?;
+        let service = manager.open_service(
+            SERVICE_NAME,
+            ServiceAccess::QUERY_STATUS | ServiceAccess::STOP | ServiceAccess::DELETE,
+        )?;
+
+        if let Ok(status) = service.query_status() {
+            if status.current_state != ServiceState::Stopped {
+                let _ = service.stop();
+            }
+        }
+
+        service.delete()
+    }
+
+    pub fn run_dispatcher() {
+        if let Err(e) = service_dispatcher::start(SERVICE_NAME, ffi_service_main) {
+            eprintln!("[Fluffy Core] Failed to start service dispatcher: {:?}", e);
+        }
+    }
+
+    fn service_main(_arguments: Vec<OsString>) {
+        if let Err(e) = run_service() {
+            eprintln!("[Fluffy Core] Service run failed: {:?}", e);
+        }
+    }
+
+    fn run_service() -> windows_service::Result<()> {
+        let running = Arc::new(AtomicBool::new(true));
+        let running_for_handler = running.clone();
+
+        let event_handler = move |control_event| -> ServiceControlHandlerResult {
+            match control_event {
+                ServiceControl::Stop | ServiceControl::Shutdown => {
+                    running_for_handler.store(false, Ordering::SeqCst);
+                    ServiceControlHandlerResult::NoError
+                }
+                ServiceControl::Interrogate => ServiceControlHandlerResult::NoError,
+                // We don't currently change behavior on lock/unlock/logon/logoff, but
+                // the SCM requires a control that's declared in `controls_accepted` to
+                // get an explicit NoError rather than NotImplemented, or it logs the
+                // service as misbehaving.
+                ServiceControl::SessionChange(_) => ServiceControlHandlerResult::NoError,
+                _ => ServiceControlHandlerResult::NotImplemented,
+            }
+        };
+
+        let status_handle = service_control_handler::register(SERVICE_NAME, event_handler)?;
+
+        status_handle.set_service_status(ServiceStatus {
+            service_type: SERVICE_TYPE,
+            current_state: ServiceState::Running,
+            controls_accepted: ServiceControlAccept::STOP
+                | ServiceControlAccept::SHUTDOWN
+                | ServiceControlAccept::SESSION_CHANGE,
+            exit_code: ServiceExitCode::Win32(0),
+            checkpoint: 0,
+            wait_hint: Duration::default(),
+            process_id: None,
+        })?;
+
+        // Running under the SCM in Session 0: no desktop, so stay headless.
+        crate::run(running, false);
+
+        status_handle.set_service_status(ServiceStatus {
+            service_type: SERVICE_TYPE,
+            current_state: ServiceState::Stopped,
+            controls_accepted: ServiceControlAccept::empty(),
+            exit_code: ServiceExitCode::Win32(0),
+            checkpoint: 0,
+            wait_hint: Duration::default(),
+            process_id: None,
+        })?;
+
+        Ok(())
+    }
+}
+
+#[cfg(not(target_os = "windows"))]
+mod other {
+    pub fn install() {
+        eprintln!("[Fluffy Core] Service mode is only supported on Windows.");
+    }
+
+    pub fn uninstall() {
+        eprintln!("[Fluffy Core] Service mode is only supported on Windows.");
+    }
+
+    pub fn run_dispatcher() {
+        eprintln!("[Fluffy Core] Service mode is only supported on Windows.");
+    }
+}
+
+#[cfg(target_os = "windows")]
+pub use windows::{install, run_dispatcher, uninstall};
+
+#[cfg(not(target_os = "windows"))]
+pub use other::{install, run_dispatcher, uninstall};
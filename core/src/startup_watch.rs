@@ -0,0 +1,148 @@
+//! Polls the same startup sources `get_startup_entries` reports on-demand (the HKCU/HKLM
+//! `...\CurrentVersion\Run` keys and the per-user/common Startup folders), diffing each
+//! snapshot against the last one and debouncing rapid bursts of changes into a single
+//! coalesced `startup_changed` broadcast. This turns the previously one-shot
+//! StartupAdd/Remove/Toggle flow into a live persistence monitor, so Fluffy notices when
+//! something *else* adds itself to autostart.
+
+use once_cell::sync::Lazy;
+use serde::Serialize;
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::{Duration, Instant};
+
+static ENABLED: AtomicBool = AtomicBool::new(true);
+
+const POLL_INTERVAL: Duration = Duration::from_millis(1000);
+const DEBOUNCE_WINDOW: Duration = Duration::from_millis(750);
+
+#[derive(Debug, Clone, Serialize)]
+pub struct StartupChange {
+    pub name: String,
+    pub source: String,
+    pub kind: String,
+    pub command: String,
+}
+
+/// Toggle whether the watcher diffs and broadcasts. The poll loop keeps running
+/// regardless, but while disabled it skips reading the registry/folders entirely; on
+/// re-enable, changes made in the meantime are reported against the last snapshot taken.
+pub fn set_enabled(enabled: bool) {
+    ENABLED.store(enabled, Ordering::SeqCst);
+}
+
+/// Spawn the background poll-and-debounce loop. Call once, alongside `IpcServer::start`.
+pub fn start() {
+    std::thread::spawn(|| {
+        let mut last_snapshot = snapshot();
+        let mut pending: HashMap<String, StartupChange> = HashMap::new();
+        let mut first_pending_change: Option<Instant> = None;
+
+        loop {
+            std::thread::sleep(POLL_INTERVAL);
+            if !ENABLED.load(Ordering::SeqCst) {
+                continue;
+            }
+
+            let current = snapshot();
+            for change in diff(&last_snapshot, &current) {
+                pending.insert(format!("{}::{}", change.source, change.name), change);
+                first_pending_change.get_or_insert_with(Instant::now);
+            }
+            last_snapshot = current;
+
+            if let Some(started) = first_pending_change {
+                if !pending.is_empty() && started.elapsed() >= DEBOUNCE_WINDOW {
+                    broadcast(pending.drain().map(|(_, v)| v).collect());
+                    first_pending_change = None;
+                }
+            }
+        }
+    });
+}
+
+struct EntryState {
+    command: String,
+    enabled: bool,
+}
+
+fn snapshot() -> HashMap<String, EntryState> {
+    crate::get_startup_entries()
+        .into_iter()
+        .map(|app| {
+            let (name, source) = split_source(&app.name);
+            (
+                format!("{}::{}", source, name),
+                EntryState {
+                    command: app.command,
+                    enabled: app.enabled,
+                },
+            )
+        })
+        .collect()
+}
+
+/// Split `"My App (HKCU)"` into `("My App", "HKCU")`, matching the suffix tagging
+/// `get_startup_entries` already applies.
+fn split_source(name: &str) -> (String, String) {
+    for source in ["HKCU", "HKLM", "Folder"] {
+        let suffix = format!(" ({})", source);
+        if let Some(real_name) = name.strip_suffix(&suffix) {
+            return (real_name.to_string(), source.to_string());
+        }
+    }
+    (name.to_string(), "Unknown".to_string())
+}
+
+fn diff(old: &HashMap<String, EntryState>, new: &HashMap<String, EntryState>) -> Vec<StartupChange> {
+    let mut changes = Vec::new();
+
+    for (key, state) in new {
+        let (source, name) = split_key(key);
+        match old.get(key) {
+            None => changes.push(StartupChange {
+                name,
+                source,
+                kind: "added".to_string(),
+                command: state.command.clone(),
+            }),
+            Some(prev) if prev.command != state.command || prev.enabled != state.enabled => {
+                changes.push(StartupChange {
+                    name,
+                    source,
+                    kind: "modified".to_string(),
+                    command: state.command.clone(),
+                })
+            }
+            _ => {}
+        }
+    }
+
+    for (key, state) in old {
+        if !new.contains_key(key) {
+            let (source, name) = split_key(key);
+            changes.push(StartupChange {
+                name,
+                source,
+                kind: "removed".to_string(),
+                command: state.command.clone(),
+            });
+        }
+    }
+
+    changes
+}
+
+fn split_key(key: &str) -> (String, String) {
+    let mut parts = key.splitn(2, "::");
+    let source = parts.next().unwrap_or("").to_string();
+    let name = parts.next().unwrap_or("").to_string();
+    (source, name)
+}
+
+fn broadcast(changes: Vec<StartupChange>) {
+    crate::ipc::server::IpcServer::broadcast_global(&crate::ipc::protocol::IpcMessage {
+        schema_version: "1.0".to_string(),
+        payload: serde_json::json!({ "type": "startup_changed", "changes": changes }),
+    });
+}
@@ -0,0 +1,134 @@
+use std::io;
+use std::process::Child;
+use std::time::{Duration, Instant};
+
+const MAX_RESTARTS: u32 = 5;
+const BASE_BACKOFF: Duration = Duration::from_secs(1);
+const MAX_BACKOFF: Duration = Duration::from_secs(30);
+
+/// Keeps a spawned child process alive: polls its exit status each tick and restarts
+/// it with exponential backoff, up to `MAX_RESTARTS`, logging along the way.
+pub struct Supervisor {
+    name: String,
+    spawn: Box<dyn FnMut() -> io::Result<Child> + Send>,
+    child: Option<Child>,
+    restart_count: u32,
+    last_restart: Instant,
+    backoff: Duration,
+}
+
+impl Supervisor {
+    /// Spawn the child immediately and wrap it for supervision.
+    pub fn new(name: &str, mut spawn: impl FnMut() -> io::Result<Child> + Send + 'static) -> Self {
+        let child = match spawn() {
+            Ok(c) => Some(c),
+            Err(e) => {
+                eprintln!("[Fluffy Core] Failed to spawn {}: {}", name, e);
+                None
+            }
+        };
+
+        Self {
+            name: name.to_string(),
+            spawn: Box::new(spawn),
+            child,
+            restart_count: 0,
+            last_restart: Instant::now(),
+            backoff: BASE_BACKOFF,
+        }
+    }
+
+    /// True if the child is currently running (non-blocking check).
+    pub fn is_alive(&mut self) -> bool {
+        match &mut self.child {
+            Some(c) => matches!(c.try_wait(), Ok(None)),
+            None => false,
+        }
+    }
+
+    /// Poll the child's exit status; if it exited, restart it once the backoff window
+    /// has elapsed, doubling the backoff after each successful restart (capped at
+    /// `MAX_BACKOFF`). Once `MAX_RESTARTS` restarts have been made, no more are tried.
+    pub fn tick(&mut self) {
+        if let Some(child) = &mut self.child {
+            match child.try_wait() {
+                Ok(Some(status)) => {
+                    if self.restart_count >= MAX_RESTARTS {
+                        eprintln!(
+                            "[Fluffy Core] {} exited ({}); restart limit ({}) reached, not restarting.",
+                            self.name, status, MAX_RESTARTS
+                        );
+                    } else {
+                        eprintln!(
+                            "[Fluffy Core] {} exited ({}); will restart.",
+                            self.name, status
+                        );
+                    }
+                    self.child = None;
+                }
+                Ok(None) => return, // still running
+                Err(e) => {
+                    eprintln!("[Fluffy Core] {} status check failed: {}", self.name, e);
+                    self.child = None;
+                }
+            }
+        }
+
+        if self.child.is_some() {
+            return;
+        }
+
+        if self.restart_count >= MAX_RESTARTS {
+            return;
+        }
+
+        if self.last_restart.elapsed() < self.backoff {
+            return;
+        }
+
+        match (self.spawn)() {
+            Ok(c) => {
+                self.restart_count += 1;
+                println!(
+                    "[Fluffy Core] Restarted {} (attempt {}/{}).",
+                    self.name, self.restart_count, MAX_RESTARTS
+                );
+                self.child = Some(c);
+                self.backoff = (self.backoff * 2).min(MAX_BACKOFF);
+            }
+            Err(e) => {
+                eprintln!("[Fluffy Core] Failed to restart {}: {}", self.name, e);
+            }
+        }
+        self.last_restart = Instant::now();
+    }
+
+    /// On Unix, send SIGTERM and give the child up to 3 seconds to exit, then force-kill
+    /// it. On Windows the child is force-killed straight away.
+    pub fn shutdown(&mut self) {
+        let Some(mut child) = self.child.take() else {
+            return;
+        };
+
+        #[cfg(unix)]
+        {
+            use nix::sys::signal::{kill, Signal};
+            use nix::unistd::Pid;
+
+            let _ = kill(Pid::from_raw(child.id() as i32), Signal::SIGTERM);
+
+            let deadline = Instant::now() + Duration::from_secs(3);
+            while Instant::now() < deadline {
+                if matches!(child.try_wait(), Ok(Some(_))) {
+                    return;
+                }
+                std::thread::sleep(Duration::from_millis(100));
+            }
+        }
+
+        // Windows has no portable graceful-terminate for an arbitrary child, and Unix
+        // children that ignored SIGTERM fall through to here too.
+        let _ = child.kill();
+        let _ = child.wait();
+    }
+}
@@ -0,0 +1,114 @@
+use rumqttc::{Client, Event, MqttOptions, Outgoing, Packet, QoS};
+use std::sync::mpsc::{self, Receiver, Sender};
+use std::time::Duration;
+
+/// Broker connection settings, read once at startup. Returns `None` when the host
+/// isn't configured so the publisher is skipped entirely rather than erroring.
+pub struct MqttConfig {
+    pub host: String,
+    pub port: u16,
+    pub topic: String,
+    pub username: Option<String>,
+    pub password: Option<String>,
+    pub qos: QoS,
+}
+
+impl MqttConfig {
+    /// `FLUFFY_MQTT_HOST` (required to enable the publisher), `FLUFFY_MQTT_PORT`
+    /// (default 1883), `FLUFFY_MQTT_USERNAME`/`FLUFFY_MQTT_PASSWORD`, and
+    /// `FLUFFY_MQTT_QOS` (0 or 1, default 0).
+    pub fn from_env() -> Option<Self> {
+        let host = std::env::var("FLUFFY_MQTT_HOST").ok()?;
+        let port = std::env::var("FLUFFY_MQTT_PORT")
+            .ok()
+            .and_then(|p| p.parse().ok())
+            .unwrap_or(1883);
+        let qos = match std::env::var("FLUFFY_MQTT_QOS").as_deref() {
+            Ok("1") => QoS::AtLeastOnce,
+            _ => QoS::AtMostOnce,
+        };
+        let hostname = hostname::get()
+            .ok()
+            .map(|h| h.to_string_lossy().into_owned())
+            .unwrap_or_else(|| "unknown-host".to_string());
+
+        Some(Self {
+            host,
+            port,
+            topic: format!("fluffy/{}/telemetry", hostname),
+            username: std::env::var("FLUFFY_MQTT_USERNAME").ok(),
+            password: std::env::var("FLUFFY_MQTT_PASSWORD").ok(),
+            qos,
+        })
+    }
+}
+
+/// Publishes the same serialized `FluffyMessage` payload the local IPC server
+/// broadcasts, to a configured MQTT broker. Connection and reconnection happen on a
+/// background thread; `publish` is a non-blocking, best-effort send so a slow or
+/// unreachable broker can never stall the monitoring loop.
+pub struct MqttPublisher {
+    tx: Sender<String>,
+}
+
+impl MqttPublisher {
+    /// Connect once at startup and keep reconnecting with backoff in the background.
+    pub fn start(config: MqttConfig) -> Self {
+        let (tx, rx) = mpsc::channel::<String>();
+        std::thread::spawn(move || run(config, rx));
+        Self { tx }
+    }
+
+    /// Queue a JSON payload for publish. Never blocks; drops silently if the
+    /// background worker has died (e.g. during shutdown).
+    pub fn publish(&self, payload: &str) {
+        let _ = self.tx.send(payload.to_string());
+    }
+}
+
+fn run(config: MqttConfig, rx: Receiver<String>) {
+    let mut backoff = Duration::from_secs(1);
+    const MAX_BACKOFF: Duration = Duration::from_secs(30);
+
+    loop {
+        let mut opts = MqttOptions::new("fluffy-core", config.host.clone(), config.port);
+        opts.set_keep_alive(Duration::from_secs(30));
+        if let (Some(user), Some(pass)) = (&config.username, &config.password) {
+            opts.set_credentials(user.clone(), pass.clone());
+        }
+
+        let (client, mut connection) = Client::new(opts, 10);
+
+        // Drain the event loop on its own thread so publishes never wait on network I/O.
+        let event_thread = std::thread::spawn(move || {
+            for notification in connection.iter() {
+                match notification {
+                    Ok(Event::Outgoing(Outgoing::Disconnect)) => break,
+                    Ok(Event::Incoming(Packet::ConnAck(_))) => {
+                        println!("[Fluffy Core] MQTT publisher connected.");
+                    }
+                    Err(e) => {
+                        eprintln!("[Fluffy Core] MQTT connection error: {}", e);
+                        break;
+                    }
+                    _ => {}
+                }
+            }
+        });
+
+        // Forward queued payloads until the connection drops, then reconnect with backoff.
+        for payload in rx.iter() {
+            if client
+                .publish(&config.topic, config.qos, false, payload.as_bytes())
+                .is_err()
+            {
+                break;
+            }
+        }
+
+        let _ = event_thread.join();
+
+        std::thread::sleep(backoff);
+        backoff = (backoff * 2).min(MAX_BACKOFF);
+    }
+}
@@ -0,0 +1,237 @@
+//! Behavior-based anomaly scoring: tracks each process's own exponential moving
+//! average across a handful of ransomware-indicative features, and flags a process
+//! when it suddenly spikes far above its own history. This is a crude per-process
+//! "that's not normal for you" detector, not a signature database — it complements,
+//! not replaces, `PROTECTED_PROCESSES`-style allow/deny lists in `ipc::receiver`.
+
+use dashmap::DashMap;
+use once_cell::sync::Lazy;
+use serde::Serialize;
+use std::collections::{HashMap, HashSet};
+use std::sync::Mutex;
+
+/// One sampling interval's stats for a process, fed into `score_processes`.
+pub struct ProcessSample {
+    pub pid: u32,
+    pub name: String,
+    pub parent_pid: Option<u32>,
+    pub working_set_kb: u64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ThreatScore {
+    pub pid: u32,
+    pub name: String,
+    pub score: f32,
+    pub reasons: Vec<String>,
+}
+
+struct Baseline {
+    working_set_ema: f32,
+    child_spawn_ema: f32,
+    doc_handle_ema: f32,
+    rename_ema: f32,
+    // Consecutive samples this pid has scored above FLAG_THRESHOLD. Requiring a streak
+    // instead of a single spike filters out one-off bursts (a build finishing, a page
+    // load) that aren't actually suspicious.
+    streak: u32,
+}
+
+const EMA_ALPHA: f32 = 0.2;
+// A metric has to clear both an absolute floor and this multiple of its own baseline
+// before it contributes to the combined score, so a process idling at ~0 doesn't get
+// flagged by noise.
+const SPIKE_RATIO: f32 = 3.0;
+const WORKING_SET_FLOOR_KB: f32 = 51_200.0; // 50 MB working set
+const CHILD_SPAWN_FLOOR_PER_MIN: f32 = 5.0;
+const DOC_HANDLE_FLOOR: f32 = 10.0;
+const RENAME_FLOOR_PER_MIN: f32 = 10.0;
+
+// Per-feature weights for the combined score: mass renames and a burst of child
+// processes are the strongest ransomware tells, so they outweigh plain growth in
+// working-set size or document handles held open.
+const WORKING_SET_WEIGHT: f32 = 1.0;
+const CHILD_SPAWN_WEIGHT: f32 = 1.5;
+const DOC_HANDLE_WEIGHT: f32 = 1.0;
+const RENAME_WEIGHT: f32 = 2.0;
+
+const FLAG_THRESHOLD: f32 = 1.0;
+const CONSECUTIVE_SAMPLES_REQUIRED: u32 = 3;
+
+// Tick interval `run()` samples processes at (see main.rs's sleep loop), used to turn
+// a per-tick count into a per-minute rate.
+const SAMPLE_INTERVAL_SECS: f32 = 2.0;
+
+static BASELINES: Lazy<DashMap<u32, Baseline>> = Lazy::new(DashMap::new);
+static LATEST: Lazy<Mutex<Vec<ThreatScore>>> = Lazy::new(|| Mutex::new(Vec::new()));
+
+/// Pids seen on the previous call, used to (a) detect which pids just exited so their
+/// baseline can be forgotten, and (b) detect which pids just appeared so their parent's
+/// child-spawn-rate feature can be credited.
+static KNOWN_PIDS: Lazy<Mutex<HashSet<u32>>> = Lazy::new(|| Mutex::new(HashSet::new()));
+
+/// Score every sampled process against its own historical EMA baseline and update
+/// that baseline for next time. Returns only processes that have scored above
+/// `FLAG_THRESHOLD` for `CONSECUTIVE_SAMPLES_REQUIRED` samples in a row.
+///
+/// Also performs the per-tick process diff: pids that disappeared since the last call
+/// have their baseline forgotten (so a reused pid doesn't inherit stale history), and
+/// pids that just appeared count toward their parent's child-spawn-rate feature.
+pub fn score_processes(samples: &[ProcessSample]) -> Vec<ThreatScore> {
+    let current_pids: HashSet<u32> = samples.iter().map(|s| s.pid).collect();
+
+    let mut known = KNOWN_PIDS.lock().unwrap();
+    for &exited_pid in known.difference(&current_pids) {
+        forget(exited_pid);
+    }
+
+    let mut new_children_by_parent: HashMap<u32, u32> = HashMap::new();
+    for sample in samples {
+        if known.contains(&sample.pid) {
+            continue;
+        }
+        if let Some(parent_pid) = sample.parent_pid {
+            *new_children_by_parent.entry(parent_pid).or_insert(0) += 1;
+        }
+    }
+
+    *known = current_pids;
+    drop(known);
+
+    samples
+        .iter()
+        .filter_map(|sample| {
+            let child_spawns_this_tick = new_children_by_parent.get(&sample.pid).copied().unwrap_or(0);
+            score_one(sample, child_spawns_this_tick)
+        })
+        .collect()
+}
+
+fn score_one(sample: &ProcessSample, child_spawns_this_tick: u32) -> Option<ThreatScore> {
+    let working_set = sample.working_set_kb as f32;
+    let child_spawn_rate = child_spawns_this_tick as f32 * (60.0 / SAMPLE_INTERVAL_SECS);
+    let doc_handles = count_doc_handles(sample.pid) as f32;
+    // No filesystem-change watcher feeds per-process rename counts (`startup_watch`
+    // only watches the registry/folder startup sources), so this is always zero
+    // rather than a guess.
+    let rename_rate = 0.0f32;
+
+    let mut baseline = BASELINES.entry(sample.pid).or_insert_with(|| Baseline {
+        working_set_ema: working_set,
+        child_spawn_ema: child_spawn_rate,
+        doc_handle_ema: doc_handles,
+        rename_ema: rename_rate,
+        streak: 0,
+    });
+
+    let mut reasons = Vec::new();
+    let mut score = 0.0f32;
+
+    score += WORKING_SET_WEIGHT
+        * flag(&mut reasons, "working-set size", working_set, baseline.working_set_ema, WORKING_SET_FLOOR_KB);
+    score += CHILD_SPAWN_WEIGHT * flag(
+        &mut reasons,
+        "child-process spawn rate",
+        child_spawn_rate,
+        baseline.child_spawn_ema,
+        CHILD_SPAWN_FLOOR_PER_MIN,
+    );
+    score += DOC_HANDLE_WEIGHT
+        * flag(&mut reasons, "open document-directory handles", doc_handles, baseline.doc_handle_ema, DOC_HANDLE_FLOOR);
+    score += RENAME_WEIGHT * flag(
+        &mut reasons,
+        "file rename/extension-change rate",
+        rename_rate,
+        baseline.rename_ema,
+        RENAME_FLOOR_PER_MIN,
+    );
+
+    baseline.working_set_ema = ema(baseline.working_set_ema, working_set);
+    baseline.child_spawn_ema = ema(baseline.child_spawn_ema, child_spawn_rate);
+    baseline.doc_handle_ema = ema(baseline.doc_handle_ema, doc_handles);
+    baseline.rename_ema = ema(baseline.rename_ema, rename_rate);
+
+    if score >= FLAG_THRESHOLD {
+        baseline.streak += 1;
+    } else {
+        baseline.streak = 0;
+    }
+
+    if baseline.streak < CONSECUTIVE_SAMPLES_REQUIRED {
+        return None;
+    }
+
+    Some(ThreatScore {
+        pid: sample.pid,
+        name: sample.name.clone(),
+        score,
+        reasons,
+    })
+}
+
+/// Count `pid`'s open file descriptors that point somewhere under one of the user's
+/// document-style directories (Documents/Desktop/Pictures/Videos/Music/Downloads) — a
+/// process with many such handles open at once is a weak signal on its own, but
+/// combined with a rename burst and a spawn storm it's a recognizable ransomware shape.
+#[cfg(target_os = "linux")]
+fn count_doc_handles(pid: u32) -> u32 {
+    let Some(home) = dirs::home_dir() else {
+        return 0;
+    };
+    let doc_dirs: Vec<std::path::PathBuf> = ["Documents", "Desktop", "Pictures", "Videos", "Music", "Downloads"]
+        .iter()
+        .map(|d| home.join(d))
+        .collect();
+
+    let fd_dir = std::path::PathBuf::from(format!("/proc/{}/fd", pid));
+    let Ok(entries) = std::fs::read_dir(&fd_dir) else {
+        return 0;
+    };
+
+    entries
+        .filter_map(Result::ok)
+        .filter_map(|entry| std::fs::read_link(entry.path()).ok())
+        .filter(|target| doc_dirs.iter().any(|dir| target.starts_with(dir)))
+        .count() as u32
+}
+
+/// No portable handle-enumeration API is wired up for this platform yet, so report
+/// zero rather than a number that looks plausible but is actually made up.
+#[cfg(not(target_os = "linux"))]
+fn count_doc_handles(_pid: u32) -> u32 {
+    0
+}
+
+fn ema(previous: f32, current: f32) -> f32 {
+    EMA_ALPHA * current + (1.0 - EMA_ALPHA) * previous
+}
+
+fn flag(reasons: &mut Vec<String>, label: &str, current: f32, baseline: f32, floor: f32) -> f32 {
+    if current > floor && current > baseline * SPIKE_RATIO {
+        let ratio = current / baseline.max(1.0);
+        reasons.push(format!(
+            "{} jumped to {:.1} vs a baseline of {:.1} ({:.1}x)",
+            label, current, baseline, ratio
+        ));
+        ratio
+    } else {
+        0.0
+    }
+}
+
+/// Replace the cached "currently flagged" list, used to serve `ScanThreats` without
+/// re-deriving scores, and to let `KillSuspicious` confirm a pid is actually flagged.
+pub fn set_latest(threats: Vec<ThreatScore>) {
+    *LATEST.lock().unwrap() = threats;
+}
+
+/// The most recently computed set of flagged processes.
+pub fn latest() -> Vec<ThreatScore> {
+    LATEST.lock().unwrap().clone()
+}
+
+/// Drop a pid's baseline once its process exits, so a reused pid doesn't inherit
+/// stale history from whatever previously held it.
+pub fn forget(pid: u32) {
+    BASELINES.remove(&pid);
+}
@@ -19,16 +19,50 @@ async fn notify_python_ui_state(connected: bool) {
     let _ = client.post(url).send().await;
 }
 
+/// Send a `Command` to core's authenticated, framed `IpcServer` (port 9001): connect,
+/// hand over the shared secret as the handshake frame, then the command itself. The
+/// legacy newline-JSON listener on port 9002 that this used to talk to was retired
+/// when the handshake was introduced (see `ipc::server` on the core side).
 async fn notify_core_ui_state(active: bool) {
-    use std::io::Write;
     use std::net::TcpStream;
 
     let cmd = serde_json::json!({
         "SetUiActive": { "active": active }
     });
 
-    if let Ok(mut stream) = TcpStream::connect("127.0.0.1:9002") {
-        let _ = writeln!(stream, "{}", cmd.to_string());
+    let Some(secret) = read_ipc_secret() else {
+        return;
+    };
+
+    let Ok(mut stream) = TcpStream::connect("127.0.0.1:9001") else {
+        return;
+    };
+
+    if write_frame(&mut stream, secret.as_bytes()).is_err() {
+        return;
+    }
+    let _ = write_frame(&mut stream, cmd.to_string().as_bytes());
+}
+
+/// Frames are length-prefixed (4-byte big-endian byte count), matching the reader on
+/// the core side (`ipc::server::read_frame`).
+fn write_frame(stream: &mut std::net::TcpStream, body: &[u8]) -> std::io::Result<()> {
+    use std::io::Write;
+    stream.write_all(&(body.len() as u32).to_be_bytes())?;
+    stream.write_all(body)
+}
+
+/// Read the shared secret core generates on its first handshake and persists to
+/// `<data_local_dir>/Fluffy/ipc_token` (see `ipc::server::load_or_create_shared_secret`),
+/// so this client can complete the handshake the framed `IpcServer` requires.
+fn read_ipc_secret() -> Option<String> {
+    let path = dirs::data_local_dir()?.join("Fluffy").join("ipc_token");
+    let contents = std::fs::read_to_string(path).ok()?;
+    let trimmed = contents.trim();
+    if trimmed.is_empty() {
+        None
+    } else {
+        Some(trimmed.to_string())
     }
 }
 